@@ -1,4 +1,11 @@
-use crate::common::*;
+use {
+  crate::{header, CliError, Helper},
+  dirs::home_dir,
+  lox::{Diagnostic, Error, Lox},
+  rustyline::{error::ReadlineError, Editor},
+  std::{cell::RefCell, fs, path::PathBuf, rc::Rc},
+  structopt::StructOpt,
+};
 
 #[derive(Debug, StructOpt)]
 pub(crate) struct Arguments {
@@ -6,35 +13,97 @@ pub(crate) struct Arguments {
 }
 
 impl Arguments {
-  pub(crate) fn run(&self) -> Result<()> {
+  pub(crate) fn run(&self) -> Result<(), CliError> {
     match self.path.to_owned() {
       Some(path) => self.file(path),
       None => self.prompt(),
     }
   }
 
-  fn file(&self, _path: PathBuf) -> Result<()> {
-    Ok(())
+  fn file(&self, path: PathBuf) -> Result<(), CliError> {
+    let src = fs::read_to_string(&path).map_err(|source| CliError::Io {
+      path: path.clone(),
+      source,
+    })?;
+
+    Self::script(Some(path), src)
   }
 
-  fn prompt(&self) -> Result<()> {
+  fn prompt(&self) -> Result<(), CliError> {
     let history = home_dir().unwrap_or_default().join(".lox_history");
 
-    let mut editor = Editor::<()>::new();
-    editor.load_history(&history).ok();
+    let lox = Rc::new(RefCell::new(Lox::new().keep_sources()));
 
-    let interpreter = Interpreter::new();
+    let mut editor = Editor::<Helper>::new();
+    editor.set_helper(Some(Helper::new(lox.clone())));
+    editor.load_history(&history).ok();
 
     loop {
-      let line = editor.readline("> ")?;
+      let line = match Self::read(&mut editor)? {
+        Some(line) => line,
+        None => continue,
+      };
 
       editor.add_history_entry(line.as_str());
       editor.save_history(&history)?;
 
-      println!(
-        "{}",
-        interpreter.clone().eval(Parser::parse(Lexer::lex(&line)?)?)
-      );
+      let result = lox.borrow_mut().eval_line(&line);
+
+      match result {
+        Ok(Some(value)) => println!("{}", value),
+        Ok(None) => {}
+        Err(error) => Self::report_line(&lox.borrow(), &error),
+      }
+    }
+  }
+
+  /// Read a line from the prompt, and then continuation lines until the input
+  /// is complete, so that a declaration or block can be spread over several
+  /// lines. Interrupting or ending input at a continuation line abandons the
+  /// lines read so far, returning `None`.
+  fn read(editor: &mut Editor<Helper>) -> Result<Option<String>, CliError> {
+    let mut line = editor.readline("> ")?;
+
+    while Lox::is_incomplete(&line) {
+      match editor.readline("... ") {
+        Ok(next) => {
+          line.push('\n');
+          line.push_str(&next);
+        }
+        Err(ReadlineError::Interrupted | ReadlineError::Eof) => {
+          return Ok(None)
+        }
+        Err(error) => return Err(error.into()),
+      }
     }
+
+    Ok(Some(line))
+  }
+
+  /// Report `error` from a line typed at the prompt. Errors raised by code
+  /// entered on earlier lines, like the body of a function being called, are
+  /// rendered against the line they point into.
+  fn report_line(lox: &Lox, error: &Error) {
+    for error in error.errors() {
+      match error
+        .position()
+        .and_then(|position| lox.source(position.source))
+      {
+        Some(src) => eprint!("{}", Diagnostic::new(error, src)),
+        None => header(error),
+      }
+    }
+  }
+
+  /// Run `src` from start to finish, keeping hold of the source so errors can
+  /// be reported against it.
+  fn script(path: Option<PathBuf>, src: String) -> Result<(), CliError> {
+    let result = Lox::new().run(&src).map(drop);
+
+    result.map_err(|error| CliError::Script {
+      path,
+      src,
+      error: Box::new(error),
+    })
   }
 }
@@ -0,0 +1,52 @@
+use crate::common::*;
+
+#[derive(Debug, Clone)]
+pub enum Callable {
+  Class(Rc<Class>),
+  Function(Rc<Function>),
+  Native(Rc<Native>),
+}
+
+impl Callable {
+  /// The number of arguments this callable expects.
+  pub fn arity(&self) -> usize {
+    match self {
+      Callable::Class(class) => class.arity(),
+      Callable::Function(function) => function.arity(),
+      Callable::Native(native) => native.arity,
+    }
+  }
+
+  pub(crate) fn call(
+    &self,
+    interpreter: &mut Interpreter,
+    arguments: Vec<Value>,
+  ) -> Result<Value> {
+    match self {
+      Callable::Class(class) => class.call(interpreter, arguments),
+      Callable::Function(function) => function.call(interpreter, arguments),
+      Callable::Native(native) => native.call(interpreter, arguments),
+    }
+  }
+}
+
+impl PartialEq for Callable {
+  fn eq(&self, other: &Self) -> bool {
+    match (self, other) {
+      (Callable::Class(a), Callable::Class(b)) => Rc::ptr_eq(a, b),
+      (Callable::Function(a), Callable::Function(b)) => Rc::ptr_eq(a, b),
+      (Callable::Native(a), Callable::Native(b)) => Rc::ptr_eq(a, b),
+      _ => false,
+    }
+  }
+}
+
+impl Display for Callable {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    match self {
+      Callable::Class(class) => write!(f, "{}", class),
+      Callable::Function(function) => write!(f, "{}", function),
+      Callable::Native(native) => write!(f, "{}", native),
+    }
+  }
+}
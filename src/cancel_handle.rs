@@ -0,0 +1,24 @@
+use crate::common::*;
+
+/// A handle for stopping a running script from another thread. The script
+/// fails with `LimitKind::Cancelled` at its next step, and every later run on
+/// the same interpreter fails the same way until the handle is reset.
+#[derive(Debug, Clone, Default)]
+pub struct CancelHandle {
+  cancelled: Arc<AtomicBool>,
+}
+
+impl CancelHandle {
+  pub fn cancel(&self) {
+    self.cancelled.store(true, Ordering::Relaxed);
+  }
+
+  pub fn is_cancelled(&self) -> bool {
+    self.cancelled.load(Ordering::Relaxed)
+  }
+
+  /// Clear a cancellation, letting the interpreter run scripts again.
+  pub fn reset(&self) {
+    self.cancelled.store(false, Ordering::Relaxed);
+  }
+}
@@ -0,0 +1,77 @@
+use crate::common::*;
+
+#[derive(Debug)]
+pub struct Class {
+  pub(crate) methods: HashMap<String, Rc<Function>>,
+  pub(crate) name: String,
+  pub(crate) superclass: Option<Rc<Class>>,
+}
+
+impl Class {
+  /// The name this class was declared with.
+  pub fn name(&self) -> &str {
+    &self.name
+  }
+
+  /// The class this one inherits from, if any.
+  pub fn superclass(&self) -> Option<&Rc<Class>> {
+    self.superclass.as_ref()
+  }
+
+  /// Look up the method `name` on this class, falling back to its superclass
+  /// chain.
+  pub(crate) fn find_method(&self, name: &str) -> Option<Rc<Function>> {
+    self.methods.get(name).cloned().or_else(|| {
+      self
+        .superclass
+        .as_ref()
+        .and_then(|superclass| superclass.find_method(name))
+    })
+  }
+
+  /// The names of the methods available on this class, including those
+  /// inherited from its superclass chain.
+  pub fn method_names(&self) -> Vec<String> {
+    let mut names = self
+      .superclass
+      .as_ref()
+      .map(|superclass| superclass.method_names())
+      .unwrap_or_default();
+
+    names.extend(self.methods.keys().cloned());
+
+    names
+  }
+
+  /// A class takes the same arguments as its initializer, if it has one.
+  pub fn arity(&self) -> usize {
+    self
+      .find_method("init")
+      .map(|initializer| initializer.arity())
+      .unwrap_or(0)
+  }
+
+  /// Calling a class creates a new instance of it and runs its initializer
+  /// on that instance.
+  pub(crate) fn call(
+    self: &Rc<Self>,
+    interpreter: &mut Interpreter,
+    arguments: Vec<Value>,
+  ) -> Result<Value> {
+    let instance = Rc::new(Instance::new(self.clone()));
+
+    if let Some(initializer) = self.find_method("init") {
+      initializer
+        .bind(instance.clone())
+        .call(interpreter, arguments)?;
+    }
+
+    Ok(Value::Instance(instance))
+  }
+}
+
+impl Display for Class {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", self.name)
+  }
+}
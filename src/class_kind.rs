@@ -0,0 +1,6 @@
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum ClassKind {
+  Class,
+  None,
+  Subclass,
+}
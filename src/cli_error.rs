@@ -0,0 +1,36 @@
+use {
+  rustyline::error::ReadlineError,
+  snafu::Snafu,
+  std::{io, path::PathBuf},
+};
+
+/// The ways the command line interface can fail, wrapping the errors of the
+/// library with those of reading scripts and lines of input.
+#[derive(Debug, Snafu)]
+#[snafu(visibility(pub(crate)))]
+pub(crate) enum CliError {
+  #[snafu(display("Failed to read `{}`: {}", path.display(), source))]
+  Io { path: PathBuf, source: io::Error },
+
+  #[snafu(context(false), display("Readline Error: {}", source))]
+  Readline { source: ReadlineError },
+
+  #[snafu(display("{}", error))]
+  Script {
+    path: Option<PathBuf>,
+    src: String,
+    error: Box<lox::Error>,
+  },
+}
+
+impl CliError {
+  /// The process exit code to use when this error ends the program, following
+  /// the conventions of `sysexits.h`.
+  pub(crate) fn code(&self) -> i32 {
+    match self {
+      CliError::Io { .. } => 66,
+      CliError::Readline { .. } => 1,
+      CliError::Script { error, .. } => error.code(),
+    }
+  }
+}
@@ -1,40 +1,59 @@
 // stdlib
 pub(crate) use std::{
-  cell::Cell,
+  cell::{Cell, RefCell},
   collections::HashMap,
   fmt::{self, Display, Formatter},
-  num,
-  path::PathBuf,
-  process,
+  io::{self, Write},
+  mem, num,
+  path::Path,
+  rc::Rc,
+  str::Chars,
+  sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+  },
+  time::{SystemTime, UNIX_EPOCH},
 };
 
 // dependencies
 pub(crate) use {
   ansi_term::{Color::Red, Style},
-  dirs::home_dir,
   lazy_static::lazy_static,
-  rustyline::{error::ReadlineError, Editor},
   snafu::Snafu,
-  structopt::StructOpt,
+  stacker::remaining_stack,
 };
 
 // structs and enums
 pub(crate) use crate::{
-  arguments::Arguments,
+  callable::Callable,
+  cancel_handle::CancelHandle,
+  class::Class,
+  class_kind::ClassKind,
+  depth::Depth,
+  environment::Environment,
   error::Error,
   expr::Expr,
+  function::Function,
+  function_declaration::FunctionDeclaration,
+  function_kind::FunctionKind,
+  instance::Instance,
   interpreter::Interpreter,
   lexer::Lexer,
+  limit_kind::LimitKind,
+  limits::Limits,
   literal::Literal,
+  native::Native,
   parser::Parser,
   position::Position,
-  printer::Printer,
+  resolver::Resolver,
+  stmt::Stmt,
   token::Token,
   token_kind::TokenKind::{self, *},
+  value::Value,
 };
 
 // traits
 pub(crate) use crate::visitor::Visitor;
 
 // type aliases
-pub(crate) type Result<T = (), E = Error> = std::result::Result<T, E>;
+pub type Result<T = (), E = Error> = std::result::Result<T, E>;
@@ -0,0 +1,19 @@
+use crate::common::*;
+
+/// How many scopes out from a variable reference its declaration is. It
+/// starts out empty, and the resolver fills it in for locals; references left
+/// empty are globals.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Depth(Cell<Option<usize>>);
+
+impl Depth {
+  /// The resolved number of scopes, or `None` for a global or a reference
+  /// that hasn't been resolved yet.
+  pub fn get(&self) -> Option<usize> {
+    self.0.get()
+  }
+
+  pub(crate) fn set(&self, depth: Option<usize>) {
+    self.0.set(depth);
+  }
+}
@@ -0,0 +1,288 @@
+use crate::common::*;
+
+/// An error rendered against the source it came from, showing the offending
+/// line with the span underlined.
+#[derive(Debug)]
+pub struct Diagnostic<'a> {
+  error: &'a Error,
+  path: Option<&'a Path>,
+  src: &'a str,
+}
+
+impl<'a> Diagnostic<'a> {
+  pub fn new(error: &'a Error, src: &'a str) -> Self {
+    Self {
+      error,
+      path: None,
+      src,
+    }
+  }
+
+  /// Name `path` as the file the source was read from.
+  pub fn path(self, path: Option<&'a Path>) -> Self {
+    Self { path, ..self }
+  }
+}
+
+impl Display for Diagnostic<'_> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    writeln!(
+      f,
+      "{}{}",
+      Red.paint("error"),
+      Style::new()
+        .bold()
+        .paint(format!(": {}", self.error.message()))
+    )?;
+
+    let position = match self.error.position() {
+      Some(position) => position,
+      None => return Ok(()),
+    };
+
+    // The position may come from a different source than the one we were
+    // given, so it can't be trusted to fall on a character boundary.
+    let start = floor_char_boundary(self.src, position.start);
+
+    let line_start = self.src[..start].rfind('\n').map_or(0, |i| i + 1);
+
+    let line_end = self.src[line_start..]
+      .find('\n')
+      .map_or(self.src.len(), |i| line_start + i);
+
+    let line = &self.src[line_start..line_end];
+
+    let before = &self.src[line_start..start];
+
+    // Keep tabs so the underline stays aligned with the echoed line.
+    let indent = before
+      .chars()
+      .map(|c| if c == '\t' { '\t' } else { ' ' })
+      .collect::<String>();
+
+    let width = self
+      .src
+      .get(start..position.current.clamp(start, line_end))
+      .map_or(0, |span| span.chars().count())
+      .max(1);
+
+    let gutter = " ".repeat(position.line.to_string().len());
+
+    let bar = Style::new().bold().paint("|");
+
+    writeln!(
+      f,
+      "{}{} {}:{}:{}",
+      gutter,
+      Style::new().bold().paint("-->"),
+      self
+        .path
+        .map_or_else(|| "<input>".into(), |path| path.display().to_string()),
+      position.line,
+      position.column
+    )?;
+
+    writeln!(f, "{} {}", gutter, bar)?;
+
+    writeln!(
+      f,
+      "{} {} {}",
+      Style::new().bold().paint(position.line.to_string()),
+      bar,
+      line
+    )?;
+
+    writeln!(
+      f,
+      "{} {} {}{}",
+      gutter,
+      bar,
+      indent,
+      Red.bold().paint("^".repeat(width))
+    )?;
+
+    if let Some(help) = self.error.help() {
+      writeln!(
+        f,
+        "{} {} {}",
+        gutter,
+        Style::new().bold().paint("="),
+        Style::new().bold().paint(format!("help: {}", help))
+      )?;
+    }
+
+    Ok(())
+  }
+}
+
+/// The largest character boundary in `src` at or before `index`.
+fn floor_char_boundary(src: &str, index: usize) -> usize {
+  (0..=index.min(src.len()))
+    .rev()
+    .find(|&i| src.is_char_boundary(i))
+    .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+  use {super::*, pretty_assertions::assert_eq};
+
+  fn render(src: &str) -> String {
+    let error = match Parser::parse(Lexer::tokens(src)).and_then(|statements| {
+      Resolver::resolve(&statements)?;
+      Interpreter::new().interpret(&statements)
+    }) {
+      Ok(()) => panic!("Expected `{}` to fail", src),
+      Err(error) => error,
+    };
+
+    let rendered = error
+      .errors()
+      .into_iter()
+      .map(|error| {
+        Diagnostic::new(error, src)
+          .path(Some(Path::new("test.lox")))
+          .to_string()
+      })
+      .collect::<String>();
+
+    String::from_utf8(strip_ansi(rendered.as_bytes())).unwrap()
+  }
+
+  fn strip_ansi(bytes: &[u8]) -> Vec<u8> {
+    let mut stripped = Vec::new();
+    let mut escape = false;
+
+    for &byte in bytes {
+      match (escape, byte) {
+        (false, 0x1b) => escape = true,
+        (false, _) => stripped.push(byte),
+        (true, b'm') => escape = false,
+        (true, _) => {}
+      }
+    }
+
+    stripped
+  }
+
+  #[test]
+  fn parser_error() {
+    assert_eq!(
+      render("var a = 1;\nprint a +;\n"),
+      "error: Expect expression.
+ --> test.lox:2:10
+  |
+2 | print a +;
+  |          ^
+"
+    );
+  }
+
+  #[test]
+  fn runtime_error() {
+    assert_eq!(
+      render("var a = 1;\n\tprint a + \"b\";"),
+      "error: Operands must be two numbers or two strings.
+ --> test.lox:2:10
+  |
+2 | \tprint a + \"b\";
+  | \t        ^
+"
+    );
+  }
+
+  #[test]
+  fn underline_width() {
+    assert_eq!(
+      render("print missing;"),
+      "error: Undefined variable 'missing'.
+ --> test.lox:1:7
+  |
+1 | print missing;
+  |       ^^^^^^^
+"
+    );
+  }
+
+  #[test]
+  fn multi_line_span() {
+    assert_eq!(
+      render("print 1;\n/* a\nb"),
+      "error: Unterminated block comment.
+ --> test.lox:2:1
+  |
+2 | /* a
+  | ^^^^
+  = help: Block comments must be closed with `*/`.
+"
+    );
+  }
+
+  #[test]
+  fn unicode() {
+    assert_eq!(
+      render("print \"héllo\" + unknown;"),
+      "error: Undefined variable 'unknown'.
+ --> test.lox:1:17
+  |
+1 | print \"héllo\" + unknown;
+  |                 ^^^^^^^
+"
+    );
+  }
+
+  #[test]
+  fn foreign_position() {
+    let error = Error::Runtime {
+      message: "Operand must be a number.".into(),
+      position: Position {
+        column: 18,
+        current: 19,
+        line: 1,
+        source: 0,
+        start: 18,
+      },
+    };
+
+    let render = |src| {
+      String::from_utf8(strip_ansi(
+        Diagnostic::new(&error, src).to_string().as_bytes(),
+      ))
+      .unwrap()
+    };
+
+    assert_eq!(
+      render("var s = \"ééééé\";"),
+      "error: Operand must be a number.
+ --> <input>:1:18
+  |
+1 | var s = \"ééééé\";
+  |              ^
+"
+    );
+
+    assert_eq!(
+      render("f();"),
+      "error: Operand must be a number.
+ --> <input>:1:18
+  |
+1 | f();
+  |     ^
+"
+    );
+  }
+
+  #[test]
+  fn help() {
+    assert_eq!(
+      render("1 = 2;"),
+      "error: Invalid assignment target.
+ --> test.lox:1:3
+  |
+1 | 1 = 2;
+  |   ^
+  = help: Only variables and fields can be assigned to.
+"
+    );
+  }
+}
@@ -0,0 +1,87 @@
+use crate::common::*;
+
+#[derive(Debug, Default)]
+pub(crate) struct Environment {
+  enclosing: Option<Rc<RefCell<Environment>>>,
+  values: HashMap<String, Value>,
+}
+
+impl Environment {
+  /// Create a new scope nested inside `enclosing`.
+  pub(crate) fn new(enclosing: Rc<RefCell<Environment>>) -> Self {
+    Self {
+      enclosing: Some(enclosing),
+      values: HashMap::new(),
+    }
+  }
+
+  /// Bind `name` to `value` in this scope, shadowing any outer binding.
+  pub(crate) fn define(&mut self, name: &str, value: Value) {
+    self.values.insert(name.to_owned(), value);
+  }
+
+  /// The names bound in this scope, not counting enclosing ones.
+  pub(crate) fn names(&self) -> Vec<String> {
+    self.values.keys().cloned().collect()
+  }
+
+  /// Look up the value bound to `name`, walking outwards through enclosing
+  /// scopes.
+  pub(crate) fn get(&self, name: &Token) -> Result<Value> {
+    let key = name.lexeme().unwrap_or_default();
+
+    if let Some(value) = self.values.get(key) {
+      return Ok(value.clone());
+    }
+
+    match &self.enclosing {
+      Some(enclosing) => enclosing.borrow().get(name),
+      None => Err(Error::undefined(name)),
+    }
+  }
+
+  /// Look up `name` in the scope exactly `distance` hops outwards, as
+  /// computed by the resolver.
+  pub(crate) fn get_at(&self, distance: usize, name: &str) -> Option<Value> {
+    match distance {
+      0 => self.values.get(name).cloned(),
+      _ => self.enclosing.as_ref()?.borrow().get_at(distance - 1, name),
+    }
+  }
+
+  /// Rebind an existing variable `name` to `value` in the nearest scope that
+  /// declares it.
+  pub(crate) fn assign(&mut self, name: &Token, value: Value) -> Result {
+    let key = name.lexeme().unwrap_or_default();
+
+    if let Some(slot) = self.values.get_mut(key) {
+      *slot = value;
+      return Ok(());
+    }
+
+    match &self.enclosing {
+      Some(enclosing) => enclosing.borrow_mut().assign(name, value),
+      None => Err(Error::undefined(name)),
+    }
+  }
+
+  /// Rebind `name` to `value` in the scope exactly `distance` hops outwards,
+  /// as computed by the resolver.
+  pub(crate) fn assign_at(
+    &mut self,
+    distance: usize,
+    name: &str,
+    value: Value,
+  ) {
+    match distance {
+      0 => {
+        self.values.insert(name.to_owned(), value);
+      }
+      _ => {
+        if let Some(enclosing) = &self.enclosing {
+          enclosing.borrow_mut().assign_at(distance - 1, name, value);
+        }
+      }
+    }
+  }
+}
@@ -3,17 +3,143 @@ use crate::common::*;
 #[derive(Debug, Snafu)]
 #[snafu(visibility(pub(crate)))]
 pub enum Error {
-  #[snafu(display("Lexer Error: {}", message))]
-  Lexer { message: String },
+  #[snafu(display(
+    "{}",
+    errors
+      .iter()
+      .map(ToString::to_string)
+      .collect::<Vec<String>>()
+      .join("\n")
+  ))]
+  Compile { errors: Vec<Error> },
 
-  #[snafu(display("Parser Error: {}", message))]
-  Parser { message: String },
+  #[snafu(display("Failed to write output: {}", source))]
+  Output { source: io::Error },
 
-  #[snafu(context(false), display("Readline Error: {}", source))]
-  Readline {
-    source: rustyline::error::ReadlineError,
+  #[snafu(display("Lexer Error: {} [line {}]", message, position.line))]
+  Lexer {
+    message: String,
+    position: Position,
+    help: Option<String>,
   },
 
+  #[snafu(display("Parser Error: {} [line {}]", message, position.line))]
+  Parser {
+    message: String,
+    position: Position,
+    help: Option<String>,
+  },
+
+  #[snafu(display("Resolver Error: {} [line {}]", message, position.line))]
+  Resolver { message: String, position: Position },
+
+  #[snafu(display("Runtime Error: {} [line {}]", message, position.line))]
+  Runtime { message: String, position: Position },
+
+  #[snafu(display(
+    "Runtime Error: {}{}",
+    kind,
+    position
+      .as_ref()
+      .map(|position| format!(" [line {}]", position.line))
+      .unwrap_or_default()
+  ))]
+  Limit {
+    kind: LimitKind,
+    position: Option<Position>,
+  },
+
+  #[snafu(display("Native Error: {}", message))]
+  Native { message: String },
+
   #[snafu(context(false), display("Failed to parse float: {}", source))]
   ParseFloat { source: num::ParseFloatError },
 }
+
+impl Error {
+  /// A runtime error raised while evaluating `token`, usually the operator or
+  /// name at fault.
+  pub(crate) fn runtime(token: &Token, message: impl Into<String>) -> Self {
+    Error::Runtime {
+      message: message.into(),
+      position: token.position.clone(),
+    }
+  }
+
+  /// An error for a native function to return, reported as a runtime error
+  /// at the call that invoked it.
+  pub fn native(message: impl Into<String>) -> Self {
+    Error::Native {
+      message: message.into(),
+    }
+  }
+
+  /// The runtime error raised when `name` doesn't refer to any variable in
+  /// scope.
+  pub(crate) fn undefined(name: &Token) -> Self {
+    Error::runtime(
+      name,
+      format!(
+        "Undefined variable '{}'.",
+        name.lexeme().unwrap_or_default()
+      ),
+    )
+  }
+
+  /// Every individual error this error stands for.
+  pub fn errors(&self) -> Vec<&Error> {
+    match self {
+      Error::Compile { errors } => {
+        errors.iter().flat_map(|error| error.errors()).collect()
+      }
+      error => vec![error],
+    }
+  }
+
+  /// The message describing this error, without any location information.
+  pub fn message(&self) -> String {
+    match self {
+      Error::Lexer { message, .. }
+      | Error::Parser { message, .. }
+      | Error::Resolver { message, .. }
+      | Error::Runtime { message, .. }
+      | Error::Native { message } => message.to_owned(),
+      Error::Limit { kind, .. } => kind.to_string(),
+      error => error.to_string(),
+    }
+  }
+
+  /// The span of source code this error points at, if any.
+  pub fn position(&self) -> Option<&Position> {
+    match self {
+      Error::Lexer { position, .. }
+      | Error::Parser { position, .. }
+      | Error::Resolver { position, .. }
+      | Error::Runtime { position, .. } => Some(position),
+      Error::Limit { position, .. } => position.as_ref(),
+      _ => None,
+    }
+  }
+
+  /// An optional note suggesting how to fix this error.
+  pub fn help(&self) -> Option<&str> {
+    match self {
+      Error::Lexer { help, .. } | Error::Parser { help, .. } => help.as_deref(),
+      _ => None,
+    }
+  }
+
+  /// The process exit code to use when this error ends the program, following
+  /// the conventions of `sysexits.h`.
+  pub fn code(&self) -> i32 {
+    match self {
+      Error::Output { .. } => 74,
+      Error::Compile { .. }
+      | Error::Lexer { .. }
+      | Error::Parser { .. }
+      | Error::ParseFloat { .. }
+      | Error::Resolver { .. } => 65,
+      Error::Limit { .. } | Error::Native { .. } | Error::Runtime { .. } => 70,
+    }
+  }
+}
@@ -1,59 +1,95 @@
 use crate::common::*;
 
 #[derive(Debug, Clone)]
-pub(crate) enum Expr<'src> {
+pub enum Expr {
   Assign {
-    name: Token<'src>,
-    value: Box<Expr<'src>>,
+    name: Token,
+    value: Box<Expr>,
+    depth: Depth,
   },
   Binary {
-    left: Box<Expr<'src>>,
-    operator: Token<'src>,
-    right: Box<Expr<'src>>,
+    left: Box<Expr>,
+    operator: Token,
+    right: Box<Expr>,
   },
   Call {
-    callee: Box<Expr<'src>>,
-    paren: Token<'src>,
-    arguments: Vec<Expr<'src>>,
+    callee: Box<Expr>,
+    paren: Token,
+    arguments: Vec<Expr>,
   },
   Get {
-    object: Box<Expr<'src>>,
-    name: Token<'src>,
+    object: Box<Expr>,
+    name: Token,
   },
   Grouping {
-    expression: Box<Expr<'src>>,
+    expression: Box<Expr>,
+  },
+  Interpolation {
+    parts: Vec<Expr>,
   },
   Literal {
     value: Literal,
   },
   Logical {
-    left: Box<Expr<'src>>,
-    operator: Token<'src>,
-    right: Box<Expr<'src>>,
+    left: Box<Expr>,
+    operator: Token,
+    right: Box<Expr>,
   },
   Set {
-    object: Box<Expr<'src>>,
-    name: Token<'src>,
-    value: Box<Expr<'src>>,
+    object: Box<Expr>,
+    name: Token,
+    value: Box<Expr>,
   },
   Super {
-    keyword: Token<'src>,
-    method: Token<'src>,
+    keyword: Token,
+    method: Token,
+    depth: Depth,
   },
   This {
-    keyword: Token<'src>,
+    keyword: Token,
+    depth: Depth,
   },
   Unary {
-    operator: Token<'src>,
-    right: Box<Expr<'src>>,
+    operator: Token,
+    right: Box<Expr>,
   },
   Variable {
-    name: Token<'src>,
+    name: Token,
+    depth: Depth,
   },
 }
 
-impl Expr<'_> {
-  pub(crate) fn accept<T>(self, visitor: impl Visitor<T>) -> T {
+impl Expr {
+  /// The position of a token in this expression, for reporting errors about
+  /// it as a whole. Literals carry no position. Nested groupings and
+  /// interpolations are searched without recursing, since this is also used
+  /// once the stack has run low.
+  pub(crate) fn position(&self) -> Option<&Position> {
+    let mut pending = vec![self];
+
+    while let Some(expr) = pending.pop() {
+      match expr {
+        Expr::Assign { name, .. }
+        | Expr::Get { name, .. }
+        | Expr::Set { name, .. }
+        | Expr::Variable { name, .. } => return Some(&name.position),
+        Expr::Binary { operator, .. }
+        | Expr::Logical { operator, .. }
+        | Expr::Unary { operator, .. } => return Some(&operator.position),
+        Expr::Call { paren, .. } => return Some(&paren.position),
+        Expr::Grouping { expression } => pending.push(expression),
+        Expr::Interpolation { parts } => pending.extend(parts.iter().rev()),
+        Expr::Literal { .. } => {}
+        Expr::Super { keyword, .. } | Expr::This { keyword, .. } => {
+          return Some(&keyword.position)
+        }
+      }
+    }
+
+    None
+  }
+
+  pub(crate) fn accept<T>(&self, visitor: &mut impl Visitor<T>) -> T {
     visitor.visit_expr(self)
   }
 }
@@ -0,0 +1,76 @@
+use crate::common::*;
+
+#[derive(Debug)]
+pub struct Function {
+  pub(crate) closure: Rc<RefCell<Environment>>,
+  pub(crate) declaration: Rc<FunctionDeclaration>,
+  pub(crate) is_initializer: bool,
+}
+
+impl Function {
+  /// The name this function was declared with.
+  pub fn name(&self) -> &str {
+    self.declaration.name.lexeme().unwrap_or_default()
+  }
+
+  /// The number of parameters this function declares.
+  pub fn arity(&self) -> usize {
+    self.declaration.params.len()
+  }
+
+  /// Create a copy of this method whose closure binds `this` to `instance`.
+  pub(crate) fn bind(&self, instance: Rc<Instance>) -> Function {
+    let mut environment = Environment::new(self.closure.clone());
+
+    environment.define("this", Value::Instance(instance));
+
+    Function {
+      closure: Rc::new(RefCell::new(environment)),
+      declaration: self.declaration.clone(),
+      is_initializer: self.is_initializer,
+    }
+  }
+
+  /// Run the function body in a fresh scope nested inside the environment it
+  /// closed over, with each parameter bound to its argument.
+  pub(crate) fn call(
+    &self,
+    interpreter: &mut Interpreter,
+    arguments: Vec<Value>,
+  ) -> Result<Value> {
+    let mut environment = Environment::new(self.closure.clone());
+
+    self.declaration.params.iter().zip(arguments).for_each(
+      |(param, argument)| {
+        environment.define(param.lexeme().unwrap_or_default(), argument)
+      },
+    );
+
+    let value =
+      interpreter.execute_block(&self.declaration.body, environment)?;
+
+    // Initializers always evaluate to the instance being initialized, even
+    // when they `return;` early.
+    if self.is_initializer {
+      return Ok(
+        self
+          .closure
+          .borrow()
+          .get_at(0, "this")
+          .unwrap_or(Value::Literal(Literal::Nil)),
+      );
+    }
+
+    Ok(value.unwrap_or(Value::Literal(Literal::Nil)))
+  }
+}
+
+impl Display for Function {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    write!(
+      f,
+      "<fn {}>",
+      self.declaration.name.lexeme().unwrap_or_default()
+    )
+  }
+}
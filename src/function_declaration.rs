@@ -0,0 +1,8 @@
+use crate::common::*;
+
+#[derive(Debug, Clone)]
+pub struct FunctionDeclaration {
+  pub name: Token,
+  pub params: Vec<Token>,
+  pub body: Vec<Stmt>,
+}
@@ -0,0 +1,7 @@
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum FunctionKind {
+  Function,
+  Initializer,
+  Method,
+  None,
+}
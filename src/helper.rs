@@ -0,0 +1,89 @@
+use {
+  lox::Lox,
+  rustyline::{
+    completion::Completer, highlight::Highlighter, hint::Hinter,
+    validate::Validator, Context, Result,
+  },
+  std::{cell::RefCell, rc::Rc},
+};
+
+/// Line editing support for the REPL.
+#[derive(Debug)]
+pub(crate) struct Helper {
+  lox: Rc<RefCell<Lox>>,
+}
+
+impl Helper {
+  /// Create a helper that completes names defined in `lox`.
+  pub(crate) fn new(lox: Rc<RefCell<Lox>>) -> Self {
+    Self { lox }
+  }
+
+  /// The byte offset at which the identifier ending `text` starts.
+  fn identifier_start(text: &str) -> usize {
+    text
+      .char_indices()
+      .rev()
+      .find(|(_, c)| !(c.is_ascii_alphanumeric() || *c == '_'))
+      .map_or(0, |(i, c)| i + c.len_utf8())
+  }
+}
+
+impl rustyline::Helper for Helper {}
+
+impl Completer for Helper {
+  type Candidate = String;
+
+  /// Complete the identifier before the cursor with a keyword or global, or,
+  /// when it follows `name.`, with a field or method of the global `name`.
+  /// Longer chains like `a.b.` aren't evaluated, so get no completions.
+  fn complete(
+    &self,
+    line: &str,
+    pos: usize,
+    _: &Context<'_>,
+  ) -> Result<(usize, Vec<String>)> {
+    let before = &line[..pos];
+
+    let start = Self::identifier_start(before);
+
+    let lox = self.lox.borrow();
+
+    let names = match before[..start].strip_suffix('.') {
+      Some(object) => {
+        let receiver = Self::identifier_start(object);
+
+        if object[..receiver].ends_with('.') {
+          Vec::new()
+        } else {
+          lox
+            .global(&object[receiver..])
+            .map(|value| value.members())
+            .unwrap_or_default()
+        }
+      }
+      None => Lox::keywords()
+        .map(str::to_owned)
+        .chain(lox.globals())
+        .collect(),
+    };
+
+    let mut candidates = names
+      .into_iter()
+      .filter(|name| name.starts_with(&before[start..]))
+      .collect::<Vec<String>>();
+
+    candidates.sort();
+    candidates.dedup();
+
+    Ok((start, candidates))
+  }
+}
+
+impl Highlighter for Helper {}
+
+impl Hinter for Helper {
+  type Hint = String;
+}
+
+impl Validator for Helper {}
@@ -0,0 +1,85 @@
+use crate::common::*;
+
+#[derive(Debug)]
+pub struct Instance {
+  class: Rc<Class>,
+  fields: RefCell<HashMap<String, Value>>,
+}
+
+impl Instance {
+  pub(crate) fn new(class: Rc<Class>) -> Self {
+    Self {
+      class,
+      fields: RefCell::new(HashMap::new()),
+    }
+  }
+
+  /// The class this is an instance of.
+  pub fn class(&self) -> &Rc<Class> {
+    &self.class
+  }
+
+  /// Look up the property `name`. Fields shadow methods, and methods are
+  /// returned bound to this instance.
+  pub(crate) fn get(self: &Rc<Self>, name: &Token) -> Result<Value> {
+    let key = name.lexeme().unwrap_or_default();
+
+    if let Some(value) = self.fields.borrow().get(key) {
+      return Ok(value.clone());
+    }
+
+    match self.class.find_method(key) {
+      Some(method) => Ok(Value::Callable(Callable::Function(Rc::new(
+        method.bind(self.clone()),
+      )))),
+      None => Err(Error::runtime(
+        name,
+        format!("Undefined property '{}'.", key),
+      )),
+    }
+  }
+
+  /// The names of this instance's fields and methods.
+  pub fn members(&self) -> Vec<String> {
+    let mut members = self.fields.borrow().keys().cloned().collect::<Vec<_>>();
+
+    members.extend(self.class.method_names());
+
+    members.sort();
+    members.dedup();
+
+    members
+  }
+
+  pub(crate) fn set(&self, name: &Token, value: Value) {
+    self
+      .fields
+      .borrow_mut()
+      .insert(name.lexeme().unwrap_or_default().to_owned(), value);
+  }
+}
+
+impl Drop for Instance {
+  /// Drop the instances reachable only through this one's fields one at a
+  /// time, rather than recursing into each in turn, so that a long linked
+  /// list of instances can't overflow the stack when it's freed.
+  fn drop(&mut self) {
+    let mut pending = mem::take(self.fields.get_mut())
+      .into_values()
+      .collect::<Vec<Value>>();
+
+    while let Some(value) = pending.pop() {
+      if let Value::Instance(instance) = value {
+        if let Ok(mut instance) = Rc::try_unwrap(instance) {
+          pending.extend(mem::take(instance.fields.get_mut()).into_values());
+        }
+      }
+    }
+  }
+}
+
+impl Display for Instance {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    write!(f, "{} instance", self.class)
+  }
+}
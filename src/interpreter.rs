@@ -1,57 +1,659 @@
 use crate::common::*;
 
-#[derive(Debug, Clone)]
-pub(crate) struct Interpreter;
+pub struct Interpreter {
+  cancel: CancelHandle,
+  depth: usize,
+  environment: Rc<RefCell<Environment>>,
+  globals: Rc<RefCell<Environment>>,
+  heap: usize,
+  limits: Limits,
+  output: Box<dyn Write>,
+  /// The position of the expression evaluated most recently, for reporting
+  /// limits hit by statements, which carry no position of their own.
+  position: Option<Position>,
+  steps: u64,
+}
 
-impl Visitor<Literal> for Interpreter {
-  fn visit_expr(&self, expr: Expr) -> Literal {
+impl Visitor<Result<Value>> for Interpreter {
+  fn visit_expr(&mut self, expr: &Expr) -> Result<Value> {
     match expr {
+      Expr::Assign { name, value, depth } => {
+        self.visit_assign(name, value, depth)
+      }
+      Expr::Call {
+        callee,
+        paren,
+        arguments,
+      } => self.visit_call(callee, paren, arguments),
+      Expr::Get { object, name } => self.visit_get(object, name),
+      Expr::Interpolation { parts } => self.visit_interpolation(parts),
       Expr::Literal { value } => self.visit_literal(value),
-      Expr::Grouping { expression } => self.visit_grouping(*expression),
-      Expr::Unary { operator, right } => self.visit_unary(operator, *right),
+      Expr::Grouping { expression } => self.visit_grouping(expression),
+      Expr::Unary { operator, right } => self.visit_unary(operator, right),
       Expr::Binary {
         left,
         operator,
         right,
-      } => self.visit_binary(*left, operator, *right),
-      _ => Literal::Nil,
+      } => self.visit_binary(left, operator, right),
+      Expr::Logical {
+        left,
+        operator,
+        right,
+      } => self.visit_logical(left, operator, right),
+      Expr::Set {
+        object,
+        name,
+        value,
+      } => self.visit_set(object, name, value),
+      Expr::Super {
+        keyword,
+        method,
+        depth,
+      } => self.visit_super(keyword, method, depth),
+      Expr::This { keyword, depth } => self.look_up_variable(keyword, depth),
+      Expr::Variable { name, depth } => self.look_up_variable(name, depth),
     }
   }
 }
 
+impl Default for Interpreter {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
 impl Interpreter {
-  pub(crate) fn new() -> Self {
-    Self
+  pub fn new() -> Self {
+    Self::with_limits(Limits::default())
+  }
+
+  /// Construct an interpreter that fails scripts exceeding `limits`.
+  pub fn with_limits(limits: Limits) -> Self {
+    let globals = Rc::new(RefCell::new(Environment::default()));
+
+    let mut interpreter = Self {
+      cancel: CancelHandle::default(),
+      depth: 0,
+      environment: globals.clone(),
+      globals,
+      heap: 0,
+      limits,
+      output: Box::new(io::stdout()),
+      position: None,
+      steps: 0,
+    };
+
+    interpreter.define_native("clock", 0, |_, _| {
+      Ok(Value::Literal(Literal::Number(
+        SystemTime::now()
+          .duration_since(UNIX_EPOCH)
+          .unwrap_or_default()
+          .as_secs_f64(),
+      )))
+    });
+
+    interpreter
+  }
+
+  /// Send the output of `print` statements to `output` rather than standard
+  /// output.
+  pub fn output(self, output: impl Write + 'static) -> Self {
+    Self {
+      output: Box::new(output),
+      ..self
+    }
+  }
+
+  /// Install `function` as a global callable named `name`, taking `arity`
+  /// arguments. Errors it returns with `Error::native` are reported as runtime
+  /// errors at the call site.
+  pub fn define_native(
+    &mut self,
+    name: &str,
+    arity: usize,
+    function: impl Fn(&mut Interpreter, &[Value]) -> Result<Value> + 'static,
+  ) {
+    let native = Native {
+      arity,
+      function: Box::new(function),
+      name: name.to_owned(),
+    };
+
+    self
+      .globals
+      .borrow_mut()
+      .define(name, Value::Callable(Callable::Native(Rc::new(native))));
+  }
+
+  /// The names of every global variable, function and class.
+  pub fn globals(&self) -> Vec<String> {
+    self.globals.borrow().names()
+  }
+
+  /// The value of the global variable `name`, if it is defined.
+  pub fn global(&self, name: &str) -> Option<Value> {
+    self.globals.borrow().get_at(0, name)
+  }
+
+  /// A handle for cancelling scripts run by this interpreter from another
+  /// thread.
+  pub fn cancel_handle(&self) -> CancelHandle {
+    self.cancel.clone()
+  }
+
+  /// Account for one unit of work, failing if the script has been cancelled,
+  /// has used up its step budget, or is about to exhaust the native stack,
+  /// however the other limits are configured.
+  fn step(&mut self) -> Result {
+    if self.cancel.is_cancelled() {
+      return Err(Self::limit(LimitKind::Cancelled, self.position.as_ref()));
+    }
+
+    if Limits::stack_exhausted() {
+      return Err(Self::limit(LimitKind::Stack, self.position.as_ref()));
+    }
+
+    self.steps += 1;
+
+    match self.limits.steps {
+      Some(steps) if self.steps > steps => {
+        Err(Self::limit(LimitKind::Steps, self.position.as_ref()))
+      }
+      _ => Ok(()),
+    }
+  }
+
+  /// Account for `bytes` of memory allocated by the script.
+  fn allocate(&mut self, bytes: usize, position: Option<&Position>) -> Result {
+    self.heap = self.heap.saturating_add(bytes);
+
+    match self.limits.heap {
+      Some(heap) if self.heap > heap => {
+        Err(Self::limit(LimitKind::Heap, position))
+      }
+      _ => Ok(()),
+    }
+  }
+
+  /// Account for a string built at runtime.
+  fn allocate_string(
+    &mut self,
+    string: &str,
+    position: Option<&Position>,
+  ) -> Result {
+    match self.limits.string_length {
+      Some(length) if string.len() > length => {
+        Err(Self::limit(LimitKind::StringLength, position))
+      }
+      _ => self.allocate(string.len(), position),
+    }
+  }
+
+  fn limit(kind: LimitKind, position: Option<&Position>) -> Error {
+    Error::Limit {
+      kind,
+      position: position.cloned(),
+    }
+  }
+
+  /// Execute every statement in `statements` in order. Each call starts with
+  /// fresh step and heap budgets.
+  pub fn interpret(&mut self, statements: &[Stmt]) -> Result {
+    self.steps = 0;
+    self.heap = 0;
+
+    self.execute_all(statements).map(|_| ())
+  }
+
+  /// Execute `statements` in order, stopping early if one of them returns.
+  fn execute_all(&mut self, statements: &[Stmt]) -> Result<Option<Value>> {
+    for statement in statements {
+      if let Some(value) = self.execute(statement)? {
+        return Ok(Some(value));
+      }
+    }
+
+    Ok(None)
+  }
+
+  /// Execute a single statement. A `return` statement unwinds through the
+  /// enclosing blocks and loops by evaluating to `Some` with the returned
+  /// value, which the nearest function call then takes as its result.
+  pub(crate) fn execute(&mut self, stmt: &Stmt) -> Result<Option<Value>> {
+    self.step()?;
+
+    match stmt {
+      Stmt::Block { statements } => self
+        .execute_block(statements, Environment::new(self.environment.clone())),
+      Stmt::Class {
+        name,
+        superclass,
+        methods,
+      } => {
+        self.execute_class(name, superclass.as_ref(), methods)?;
+        Ok(None)
+      }
+      Stmt::Expression { expression } => {
+        self.eval(expression)?;
+        Ok(None)
+      }
+      Stmt::Function { declaration } => {
+        self.allocate(
+          mem::size_of::<Function>(),
+          Some(&declaration.name.position),
+        )?;
+
+        let function = Function {
+          closure: self.environment.clone(),
+          declaration: declaration.clone(),
+          is_initializer: false,
+        };
+
+        self.environment.borrow_mut().define(
+          declaration.name.lexeme().unwrap_or_default(),
+          Value::Callable(Callable::Function(Rc::new(function))),
+        );
+
+        Ok(None)
+      }
+      Stmt::If {
+        condition,
+        then_branch,
+        else_branch,
+      } => {
+        if self.eval(condition)?.is_truthy() {
+          self.execute(then_branch)
+        } else if let Some(else_branch) = else_branch {
+          self.execute(else_branch)
+        } else {
+          Ok(None)
+        }
+      }
+      Stmt::Print { expression } => {
+        let value = self.eval(expression)?;
+
+        writeln!(self.output, "{}", value)
+          .map_err(|source| Error::Output { source })?;
+
+        Ok(None)
+      }
+      Stmt::Return { value, .. } => Ok(Some(match value {
+        Some(value) => self.eval(value)?,
+        None => Value::Literal(Literal::Nil),
+      })),
+      Stmt::Var { name, initializer } => {
+        let value = match initializer {
+          Some(initializer) => self.eval(initializer)?,
+          None => Value::Literal(Literal::Nil),
+        };
+
+        self.allocate(
+          mem::size_of::<Value>() + name.lexeme().unwrap_or_default().len(),
+          Some(&name.position),
+        )?;
+
+        self
+          .environment
+          .borrow_mut()
+          .define(name.lexeme().unwrap_or_default(), value);
+
+        Ok(None)
+      }
+      Stmt::While { condition, body } => {
+        while self.eval(condition)?.is_truthy() {
+          if let Some(value) = self.execute(body)? {
+            return Ok(Some(value));
+          }
+        }
+
+        Ok(None)
+      }
+    }
   }
 
-  pub(crate) fn eval(&self, expr: Expr) -> Literal {
-    expr.accept(self.clone())
+  /// Declare the class `name`. Its methods close over an extra scope binding
+  /// `super` when the class has a superclass.
+  fn execute_class(
+    &mut self,
+    name: &Token,
+    superclass: Option<&Expr>,
+    methods: &[Rc<FunctionDeclaration>],
+  ) -> Result {
+    let superclass = match superclass {
+      Some(expr) => match self.eval(expr)? {
+        Value::Callable(Callable::Class(class)) => Some(class),
+        _ => {
+          return Err(Error::runtime(
+            match expr {
+              Expr::Variable { name, .. } => name,
+              _ => name,
+            },
+            "Superclass must be a class.",
+          ))
+        }
+      },
+      None => None,
+    };
+
+    let key = name.lexeme().unwrap_or_default();
+
+    self
+      .environment
+      .borrow_mut()
+      .define(key, Value::Literal(Literal::Nil));
+
+    let closure = match &superclass {
+      Some(superclass) => {
+        let mut environment = Environment::new(self.environment.clone());
+
+        environment.define(
+          "super",
+          Value::Callable(Callable::Class(superclass.clone())),
+        );
+
+        Rc::new(RefCell::new(environment))
+      }
+      None => self.environment.clone(),
+    };
+
+    let methods = methods
+      .iter()
+      .map(|declaration| {
+        let method = declaration.name.lexeme().unwrap_or_default();
+
+        (
+          method.to_owned(),
+          Rc::new(Function {
+            closure: closure.clone(),
+            declaration: declaration.clone(),
+            is_initializer: method == "init",
+          }),
+        )
+      })
+      .collect();
+
+    let class = Class {
+      methods,
+      name: key.to_owned(),
+      superclass,
+    };
+
+    self
+      .environment
+      .borrow_mut()
+      .assign(name, Value::Callable(Callable::Class(Rc::new(class))))
   }
 
-  fn visit_literal(&self, value: Literal) -> Literal {
-    value
+  /// Execute `statements` inside `environment`, restoring the current
+  /// environment afterwards even if execution fails.
+  pub(crate) fn execute_block(
+    &mut self,
+    statements: &[Stmt],
+    environment: Environment,
+  ) -> Result<Option<Value>> {
+    let previous =
+      mem::replace(&mut self.environment, Rc::new(RefCell::new(environment)));
+
+    let result = self.execute_all(statements);
+
+    self.environment = previous;
+
+    result
+  }
+
+  pub(crate) fn eval(&mut self, expr: &Expr) -> Result<Value> {
+    if let Some(position) = expr.position() {
+      self.position = Some(position.clone());
+    }
+
+    self.step()?;
+
+    expr.accept(self)
+  }
+
+  fn visit_assign(
+    &mut self,
+    name: &Token,
+    value: &Expr,
+    depth: &Depth,
+  ) -> Result<Value> {
+    let value = self.eval(value)?;
+
+    match depth.get() {
+      Some(distance) => self.environment.borrow_mut().assign_at(
+        distance,
+        name.lexeme().unwrap_or_default(),
+        value.clone(),
+      ),
+      None => self.globals.borrow_mut().assign(name, value.clone())?,
+    }
+
+    Ok(value)
+  }
+
+  fn visit_call(
+    &mut self,
+    callee: &Expr,
+    paren: &Token,
+    arguments: &[Expr],
+  ) -> Result<Value> {
+    let callee = self.eval(callee)?;
+
+    let arguments = arguments
+      .iter()
+      .map(|argument| self.eval(argument))
+      .collect::<Result<Vec<_>>>()?;
+
+    let callable = match callee {
+      Value::Callable(callable) => callable,
+      _ => {
+        return Err(Error::runtime(
+          paren,
+          "Can only call functions and classes.",
+        ))
+      }
+    };
+
+    if arguments.len() != callable.arity() {
+      return Err(Error::runtime(
+        paren,
+        format!(
+          "Expected {} arguments but got {}.",
+          callable.arity(),
+          arguments.len()
+        ),
+      ));
+    }
+
+    if self.depth >= self.limits.call_depth {
+      return Err(Self::limit(LimitKind::CallDepth, Some(&paren.position)));
+    }
+
+    self.allocate(
+      match callable {
+        Callable::Class(_) => {
+          mem::size_of::<Environment>() + mem::size_of::<Instance>()
+        }
+        _ => mem::size_of::<Environment>(),
+      },
+      Some(&paren.position),
+    )?;
+
+    self.depth += 1;
+    let result = callable.call(self, arguments);
+    self.depth -= 1;
+
+    result.map_err(|error| match error {
+      Error::Native { message } => Error::runtime(paren, message),
+      error => error,
+    })
   }
 
-  fn visit_grouping(&self, expr: Expr) -> Literal {
+  fn visit_get(&mut self, object: &Expr, name: &Token) -> Result<Value> {
+    match self.eval(object)? {
+      Value::Instance(instance) => instance.get(name),
+      _ => Err(Error::runtime(name, "Only instances have properties.")),
+    }
+  }
+
+  /// Interpolated strings join the display form of every part, so values of
+  /// any type can be interpolated.
+  fn visit_interpolation(&mut self, parts: &[Expr]) -> Result<Value> {
+    let mut value = String::new();
+
+    for part in parts {
+      value.push_str(&self.eval(part)?.to_string());
+    }
+
+    self.allocate_string(&value, parts.iter().find_map(Expr::position))?;
+
+    Ok(Value::Literal(Literal::String(value)))
+  }
+
+  fn visit_literal(&mut self, value: &Literal) -> Result<Value> {
+    Ok(Value::Literal(value.clone()))
+  }
+
+  fn visit_grouping(&mut self, expr: &Expr) -> Result<Value> {
     self.eval(expr)
   }
 
-  fn visit_unary(&self, operator: Token, right: Expr) -> Literal {
-    match (operator.kind, self.eval(right)) {
-      (Minus, Literal::Number(value)) => Literal::Number(-1.0 * value),
-      (Bang, Literal::Boolean(value)) => Literal::Boolean(!value),
-      _ => Literal::Nil,
+  /// Logical operators short-circuit and evaluate to whichever operand
+  /// decided the result, not necessarily a boolean.
+  fn visit_logical(
+    &mut self,
+    left: &Expr,
+    operator: &Token,
+    right: &Expr,
+  ) -> Result<Value> {
+    let left = self.eval(left)?;
+
+    match (&operator.kind, left.is_truthy()) {
+      (Or, true) | (And, false) => Ok(left),
+      _ => self.eval(right),
     }
   }
 
-  fn visit_binary(&self, left: Expr, operator: Token, right: Expr) -> Literal {
-    match (self.eval(left), operator.kind, self.eval(right)) {
-      (Literal::Number(l), BangEqual, Literal::Number(r)) => {
-        Literal::Boolean(l != r)
+  fn visit_set(
+    &mut self,
+    object: &Expr,
+    name: &Token,
+    value: &Expr,
+  ) -> Result<Value> {
+    let instance = match self.eval(object)? {
+      Value::Instance(instance) => instance,
+      _ => return Err(Error::runtime(name, "Only instances have fields.")),
+    };
+
+    let value = self.eval(value)?;
+
+    self.allocate(
+      mem::size_of::<Value>() + name.lexeme().unwrap_or_default().len(),
+      Some(&name.position),
+    )?;
+
+    instance.set(name, value.clone());
+    Ok(value)
+  }
+
+  /// Look up `method` on the superclass of the class whose method is running,
+  /// bound to the current `this`.
+  fn visit_super(
+    &mut self,
+    keyword: &Token,
+    method: &Token,
+    depth: &Depth,
+  ) -> Result<Value> {
+    let distance = depth.get().ok_or_else(|| Error::undefined(keyword))?;
+
+    let environment = self.environment.borrow();
+
+    // The scope binding `this` always sits directly inside the one binding
+    // `super`.
+    let (superclass, instance) = match (
+      environment.get_at(distance, "super"),
+      environment.get_at(distance - 1, "this"),
+    ) {
+      (
+        Some(Value::Callable(Callable::Class(superclass))),
+        Some(Value::Instance(instance)),
+      ) => (superclass, instance),
+      _ => return Err(Error::undefined(keyword)),
+    };
+
+    let key = method.lexeme().unwrap_or_default();
+
+    match superclass.find_method(key) {
+      Some(method) => Ok(Value::Callable(Callable::Function(Rc::new(
+        method.bind(instance),
+      )))),
+      None => Err(Error::runtime(
+        method,
+        format!("Undefined property '{}'.", key),
+      )),
+    }
+  }
+
+  /// Look up `name` at the scope distance the resolver computed, or in the
+  /// globals if it wasn't resolved to a local.
+  fn look_up_variable(&mut self, name: &Token, depth: &Depth) -> Result<Value> {
+    match depth.get() {
+      Some(distance) => self
+        .environment
+        .borrow()
+        .get_at(distance, name.lexeme().unwrap_or_default())
+        .ok_or_else(|| Error::undefined(name)),
+      None => self.globals.borrow().get(name),
+    }
+  }
+
+  fn visit_unary(&mut self, operator: &Token, right: &Expr) -> Result<Value> {
+    Ok(Value::Literal(match (&operator.kind, self.eval(right)?) {
+      (Minus, Value::Literal(Literal::Number(value))) => {
+        Literal::Number(-value)
       }
-      (Literal::Number(l), EqualEqual, Literal::Number(r)) => {
-        Literal::Boolean(l == r)
+      (Minus, _) => {
+        return Err(Error::runtime(operator, "Operand must be a number."))
+      }
+      (_, value) => Literal::Boolean(!value.is_truthy()),
+    }))
+  }
+
+  fn visit_binary(
+    &mut self,
+    left: &Expr,
+    operator: &Token,
+    right: &Expr,
+  ) -> Result<Value> {
+    match (self.eval(left)?, &operator.kind, self.eval(right)?) {
+      // Values of different types are never equal, and everything else is
+      // compared by value, with `NaN` unequal to itself as in IEEE 754.
+      (left, EqualEqual, right) => {
+        Ok(Value::Literal(Literal::Boolean(left == right)))
+      }
+      (left, BangEqual, right) => {
+        Ok(Value::Literal(Literal::Boolean(left != right)))
+      }
+      (Value::Literal(left), _, Value::Literal(right)) => {
+        let literal = Self::binary(left, operator, right)?;
+
+        if let Literal::String(string) = &literal {
+          self.allocate_string(string, Some(&operator.position))?;
+        }
+
+        Ok(Value::Literal(literal))
       }
+      _ => Err(Self::binary_mismatch(operator)),
+    }
+  }
+
+  /// Apply the binary `operator` to two literal operands.
+  fn binary(
+    left: Literal,
+    operator: &Token,
+    right: Literal,
+  ) -> Result<Literal> {
+    Ok(match (left, &operator.kind, right) {
       (Literal::Number(l), Greater, Literal::Number(r)) => {
         Literal::Boolean(l > r)
       }
@@ -66,12 +668,6 @@ impl Interpreter {
       (Literal::Number(l), Plus, Literal::Number(r)) => Literal::Number(l + r),
       (Literal::Number(l), Slash, Literal::Number(r)) => Literal::Number(l / r),
       (Literal::Number(l), Star, Literal::Number(r)) => Literal::Number(l * r),
-      (Literal::String(l), BangEqual, Literal::String(r)) => {
-        Literal::Boolean(l != r)
-      }
-      (Literal::String(l), EqualEqual, Literal::String(r)) => {
-        Literal::Boolean(l == r)
-      }
       (Literal::String(l), Greater, Literal::String(r)) => {
         Literal::Boolean(l > r)
       }
@@ -85,17 +681,50 @@ impl Interpreter {
       (Literal::String(l), Plus, Literal::String(r)) => {
         Literal::String(format!("{}{}", l, r))
       }
-      _ => Literal::Nil,
+      _ => return Err(Self::binary_mismatch(operator)),
+    })
+  }
+
+  /// The error raised when `operator` is applied to operands of types it
+  /// doesn't support.
+  fn binary_mismatch(operator: &Token) -> Error {
+    match operator.kind {
+      Plus | Greater | GreaterEqual | Less | LessEqual => {
+        Error::runtime(operator, "Operands must be two numbers or two strings.")
+      }
+      _ => Error::runtime(operator, "Operands must be numbers."),
     }
   }
 }
 
+impl fmt::Debug for Interpreter {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    f.debug_struct("Interpreter")
+      .field("cancel", &self.cancel)
+      .field("depth", &self.depth)
+      .field("environment", &self.environment)
+      .field("globals", &self.globals)
+      .field("heap", &self.heap)
+      .field("limits", &self.limits)
+      .field("position", &self.position)
+      .field("steps", &self.steps)
+      .finish()
+  }
+}
+
 #[cfg(test)]
 mod tests {
-  use {super::*, pretty_assertions::assert_eq};
+  use {
+    super::*, crate::testing::Buffer, pretty_assertions::assert_eq, std::thread,
+  };
+
+  type NativeFn = fn(&mut Interpreter, &[Value]) -> Result<Value>;
 
   struct Test {
-    interpreter: Interpreter,
+    limits: Limits,
+    natives: Vec<(&'static str, usize, NativeFn)>,
+    printed: Vec<String>,
+    program: String,
     source: Vec<String>,
     expected: Vec<String>,
   }
@@ -103,15 +732,46 @@ mod tests {
   impl Test {
     fn new() -> Self {
       Self {
-        interpreter: Interpreter::new(),
+        limits: Limits::default(),
+        natives: Vec::new(),
+        printed: Vec::new(),
+        program: String::new(),
         source: Vec::new(),
         expected: Vec::new(),
       }
     }
 
+    fn native(
+      mut self,
+      name: &'static str,
+      arity: usize,
+      function: NativeFn,
+    ) -> Self {
+      self.natives.push((name, arity, function));
+      self
+    }
+
+    fn limits(self, limits: Limits) -> Self {
+      Self { limits, ..self }
+    }
+
+    fn printed(self, printed: Vec<&str>) -> Self {
+      Self {
+        printed: printed.iter().map(|s| s.to_string()).collect(),
+        ..self
+      }
+    }
+
+    fn program(self, program: &str) -> Self {
+      Self {
+        program: program.to_owned(),
+        ..self
+      }
+    }
+
     fn source(self, source: Vec<&str>) -> Self {
       Self {
-        source: source.iter().map(|s| s.to_string()).collect(),
+        source: source.iter().map(|s| format!("{};", s)).collect(),
         ..self
       }
     }
@@ -124,18 +784,37 @@ mod tests {
     }
 
     fn run(&self) -> Result {
+      let output = Buffer::default();
+
+      let mut interpreter =
+        Interpreter::with_limits(self.limits.clone()).output(output.clone());
+
+      for (name, arity, function) in &self.natives {
+        interpreter.define_native(name, *arity, *function);
+      }
+
+      let program = Parser::parse(Lexer::lex(&self.program)?)?;
+
+      Resolver::resolve(&program)?;
+
+      interpreter.interpret(&program)?;
+
       self.source.iter().zip(self.expected.clone()).try_for_each(
         |(source, expected)| -> Result {
-          assert_eq!(
-            self
-              .interpreter
-              .eval(Parser::parse(Lexer::lex(source)?)?)
-              .to_string(),
-            expected
-          );
+          let expression = match Parser::parse(Lexer::lex(source)?)?.as_slice()
+          {
+            [Stmt::Expression { expression }] => expression.clone(),
+            statements => panic!("Expected expression, got {:?}", statements),
+          };
+
+          assert_eq!(interpreter.eval(&expression)?.to_string(), expected);
           Ok(())
         },
-      )
+      )?;
+
+      assert_eq!(output.contents().lines().collect::<Vec<_>>(), self.printed);
+
+      Ok(())
     }
   }
 
@@ -173,6 +852,14 @@ mod tests {
       .run()
   }
 
+  #[test]
+  fn booleans() -> Result {
+    Test::new()
+      .source(vec!["true", "false", "!true"])
+      .expected(vec!["true", "false", "false"])
+      .run()
+  }
+
   #[test]
   fn string_concatenation() -> Result {
     Test::new()
@@ -180,4 +867,758 @@ mod tests {
       .expected(vec!["11", "foobar"])
       .run()
   }
+
+  #[test]
+  fn string_escapes() -> Result {
+    Test::new()
+      .source(vec![
+        r#""a\nb""#,
+        r#""\ta\"b\"\\""#,
+        r#""\u{48}\u{e9}\u{1F980}""#,
+        r#""\${x}""#,
+      ])
+      .expected(vec!["a\nb", "\ta\"b\"\\", "Hé🦀", "${x}"])
+      .run()
+  }
+
+  #[test]
+  fn print() -> Result {
+    Test::new()
+      .program(
+        "
+        print 1 + 2;
+        print \"a\" + \"b\";
+        print nil;
+        class A {}
+        print A();
+        ",
+      )
+      .printed(vec!["3", "ab", "nil", "A instance"])
+      .run()
+  }
+
+  #[test]
+  fn print_error() {
+    struct Closed;
+
+    impl Write for Closed {
+      fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+        Err(io::ErrorKind::BrokenPipe.into())
+      }
+
+      fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+      }
+    }
+
+    let program = Parser::parse(Lexer::tokens("print 1;")).unwrap();
+
+    let error = Interpreter::new()
+      .output(Closed)
+      .interpret(&program)
+      .unwrap_err();
+
+    assert!(matches!(error, Error::Output { .. }));
+    assert_eq!(error.code(), 74);
+  }
+
+  #[test]
+  fn string_interpolation() -> Result {
+    Test::new()
+      .program(
+        "
+        var name = \"world\";
+        class Point {}
+        fun greet(who) { return \"hello ${who}\"; }
+        ",
+      )
+      .source(vec![
+        r#""hello ${name}!""#,
+        r#""${1 + 2} and ${nil}, ${true}""#,
+        r#""${name}${name}""#,
+        r#""outer ${greet("inner ${name}")}""#,
+        r#""${Point} ${Point()} ${greet}""#,
+        r#""${"\"quoted\""}""#,
+      ])
+      .expected(vec![
+        "hello world!",
+        "3 and nil, true",
+        "worldworld",
+        "outer hello inner world",
+        "Point Point instance <fn greet>",
+        "\"quoted\"",
+      ])
+      .run()
+  }
+
+  #[test]
+  fn variables() -> Result {
+    Test::new()
+      .program("var a = 1; var b; var c = a + 2;")
+      .source(vec!["a", "b", "c", "a = 5", "a"])
+      .expected(vec!["1", "nil", "3", "5", "5"])
+      .run()
+  }
+
+  #[test]
+  fn block_scope() -> Result {
+    Test::new()
+      .program(
+        "
+        var a = \"global a\";
+        var b = \"global b\";
+        {
+          var a = \"inner a\";
+          b = a;
+        }
+        ",
+      )
+      .source(vec!["a", "b"])
+      .expected(vec!["global a", "inner a"])
+      .run()
+  }
+
+  #[test]
+  fn undefined_variable() -> Result {
+    let error = Test::new()
+      .program("var a = 1;\nprint b;")
+      .run()
+      .unwrap_err();
+
+    assert_eq!(
+      error.to_string(),
+      "Runtime Error: Undefined variable 'b'. [line 2]"
+    );
+
+    let error = Test::new().program("c = 1;").run().unwrap_err();
+
+    assert_eq!(
+      error.to_string(),
+      "Runtime Error: Undefined variable 'c'. [line 1]"
+    );
+
+    Ok(())
+  }
+
+  #[test]
+  fn logical() -> Result {
+    Test::new()
+      .source(vec![
+        "\"hi\" or 2",
+        "nil or \"yes\"",
+        "false and 1",
+        "1 and 2",
+        "nil and undefined",
+        "true or undefined",
+      ])
+      .expected(vec!["hi", "yes", "false", "2", "nil", "true"])
+      .run()
+  }
+
+  #[test]
+  fn if_else() -> Result {
+    Test::new()
+      .program(
+        "
+        var a;
+        var b;
+        var c;
+        if (true) a = 1; else a = 2;
+        if (nil) b = 1; else b = 2;
+        if (0) c = \"zero is truthy\";
+        ",
+      )
+      .source(vec!["a", "b", "c"])
+      .expected(vec!["1", "2", "zero is truthy"])
+      .run()
+  }
+
+  #[test]
+  fn loops() -> Result {
+    Test::new()
+      .program(
+        "
+        var i = 0;
+        var sum = 0;
+        while (i < 5) {
+          sum = sum + i;
+          i = i + 1;
+        }
+        var product = 1;
+        for (var j = 1; j <= 5; j = j + 1) product = product * j;
+        ",
+      )
+      .source(vec!["i", "sum", "product"])
+      .expected(vec!["5", "10", "120"])
+      .run()
+  }
+
+  #[test]
+  fn for_loop_scope() -> Result {
+    let error = Test::new()
+      .program("for (var i = 0; i < 1; i = i + 1) {}\nprint i;")
+      .run()
+      .unwrap_err();
+
+    assert_eq!(
+      error.to_string(),
+      "Runtime Error: Undefined variable 'i'. [line 2]"
+    );
+
+    Ok(())
+  }
+
+  #[test]
+  fn functions() -> Result {
+    Test::new()
+      .program(
+        "
+        fun add(a, b) {
+          return a + b;
+        }
+
+        fun nothing() {}
+
+        fun fib(n) {
+          if (n < 2) return n;
+          return fib(n - 1) + fib(n - 2);
+        }
+        ",
+      )
+      .source(vec!["add(1, 2)", "nothing()", "fib(10)", "add"])
+      .expected(vec!["3", "nil", "55", "<fn add>"])
+      .run()
+  }
+
+  #[test]
+  fn closures() -> Result {
+    Test::new()
+      .program(
+        "
+        fun counter() {
+          var i = 0;
+          fun count() {
+            i = i + 1;
+            return i;
+          }
+          return count;
+        }
+
+        var a = counter();
+        var b = counter();
+        a();
+        a();
+        ",
+      )
+      .source(vec!["a()", "b()"])
+      .expected(vec!["3", "1"])
+      .run()
+  }
+
+  #[test]
+  fn call_errors() -> Result {
+    let error = Test::new()
+      .program("fun f(a) {}\nf(1, 2);")
+      .run()
+      .unwrap_err();
+
+    assert_eq!(
+      error.to_string(),
+      "Runtime Error: Expected 1 arguments but got 2. [line 2]"
+    );
+
+    let error = Test::new().program("\"str\"();").run().unwrap_err();
+
+    assert_eq!(
+      error.to_string(),
+      "Runtime Error: Can only call functions and classes. [line 1]"
+    );
+
+    Ok(())
+  }
+
+  #[test]
+  fn stack_overflow() {
+    let error = Test::new()
+      .program("fun f() { f(); }\nf();")
+      .run()
+      .unwrap_err();
+
+    assert_eq!(error.to_string(), "Runtime Error: Stack overflow. [line 1]");
+  }
+
+  #[test]
+  fn classes() -> Result {
+    Test::new()
+      .program(
+        "
+        class Point {
+          init(x, y) {
+            this.x = x;
+            this.y = y;
+          }
+
+          sum() {
+            return this.x + this.y;
+          }
+        }
+
+        var point = Point(1, 2);
+        var sum = point.sum;
+        point.x = 10;
+        ",
+      )
+      .source(vec![
+        "Point",
+        "point",
+        "point.y",
+        "sum()",
+        "point.init(3, 4)",
+      ])
+      .expected(vec!["Point", "Point instance", "2", "12", "Point instance"])
+      .run()
+  }
+
+  #[test]
+  fn inheritance() -> Result {
+    Test::new()
+      .program(
+        "
+        class A {
+          name() { return \"A\"; }
+          greet() { return \"hello from \" + this.name(); }
+        }
+
+        class B < A {
+          name() { return \"B\"; }
+          greet() { return super.greet() + \"!\"; }
+        }
+
+        class C < B {}
+        ",
+      )
+      .source(vec!["A().greet()", "B().greet()", "C().greet()"])
+      .expected(vec!["hello from A", "hello from B!", "hello from B!"])
+      .run()
+  }
+
+  #[test]
+  fn class_errors() -> Result {
+    let error = Test::new()
+      .program("class A {}\nA().missing;")
+      .run()
+      .unwrap_err();
+
+    assert_eq!(
+      error.to_string(),
+      "Runtime Error: Undefined property 'missing'. [line 2]"
+    );
+
+    let error = Test::new()
+      .program("var A = 1;\nclass B < A {}")
+      .run()
+      .unwrap_err();
+
+    assert_eq!(
+      error.to_string(),
+      "Runtime Error: Superclass must be a class. [line 2]"
+    );
+
+    let error = Test::new().program("1.field = 2;").run().unwrap_err();
+
+    assert_eq!(
+      error.to_string(),
+      "Runtime Error: Only instances have fields. [line 1]"
+    );
+
+    Ok(())
+  }
+
+  #[test]
+  fn closure_binding() -> Result {
+    Test::new()
+      .program(
+        "
+        var a = \"global\";
+        var first;
+        var second;
+        {
+          fun show() {
+            return a;
+          }
+
+          first = show();
+          var a = \"block\";
+          second = show();
+        }
+        ",
+      )
+      .source(vec!["first", "second"])
+      .expected(vec!["global", "global"])
+      .run()
+  }
+
+  #[test]
+  fn type_errors() -> Result {
+    for (program, message) in [
+      ("-\"x\";", "Operand must be a number."),
+      ("1 + \"a\";", "Operands must be two numbers or two strings."),
+      ("\"a\" < 1;", "Operands must be two numbers or two strings."),
+      ("nil - 1;", "Operands must be numbers."),
+      ("true * 2;", "Operands must be numbers."),
+      ("fun f() {}\nf / 2;", "Operands must be numbers."),
+    ] {
+      let error = Test::new().program(program).run().unwrap_err();
+
+      assert_eq!(
+        error.to_string(),
+        format!(
+          "Runtime Error: {} [line {}]",
+          message,
+          program.lines().count()
+        )
+      );
+    }
+
+    Ok(())
+  }
+
+  #[test]
+  fn truthiness() -> Result {
+    Test::new()
+      .program("fun f() {}\nclass A {}")
+      .source(vec![
+        "!nil", "!false", "!true", "!0", "!\"\"", "!f", "!A", "!A()", "!!nil",
+      ])
+      .expected(vec![
+        "true", "true", "false", "false", "false", "false", "false", "false",
+        "false",
+      ])
+      .run()
+  }
+
+  #[test]
+  fn equality() -> Result {
+    let literals = ["nil", "true", "false", "0", "1", "\"\"", "\"a\"", "\"0\""];
+
+    let mut source = Vec::new();
+    let mut expected = Vec::new();
+
+    for (i, left) in literals.iter().enumerate() {
+      for (j, right) in literals.iter().enumerate() {
+        source.push(format!("{} == {}", left, right));
+        expected.push((i == j).to_string());
+        source.push(format!("{} != {}", left, right));
+        expected.push((i != j).to_string());
+      }
+    }
+
+    Test::new()
+      .source(source.iter().map(String::as_str).collect())
+      .expected(expected.iter().map(String::as_str).collect())
+      .run()
+  }
+
+  #[test]
+  fn reference_equality() -> Result {
+    Test::new()
+      .program(
+        "
+        fun f() {}
+        fun g() {}
+        class A { m() {} }
+        var a = A();
+        ",
+      )
+      .source(vec![
+        "f == f",
+        "f == g",
+        "A == A",
+        "a == a",
+        "a == A()",
+        "a.m == a.m",
+      ])
+      .expected(vec!["true", "false", "true", "true", "false", "false"])
+      .run()
+  }
+
+  #[test]
+  fn nan_equality() -> Result {
+    Test::new()
+      .program("var nan = 0 / 0;")
+      .source(vec!["nan == 0", "nan != 1", "nan == nan", "nan != nan"])
+      .expected(vec!["false", "true", "false", "true"])
+      .run()
+  }
+
+  #[test]
+  fn natives() -> Result {
+    Test::new()
+      .native("add", 2, |_, arguments| match arguments {
+        [Value::Literal(Literal::Number(a)), Value::Literal(Literal::Number(b))] => {
+          Ok(Value::Literal(Literal::Number(a + b)))
+        }
+        _ => Err(Error::native("Arguments must be numbers.")),
+      })
+      .native("nothing", 0, |_, _| Ok(Value::Literal(Literal::Nil)))
+      .program("fun twice(x) { return add(x, x); }")
+      .source(vec!["add(1, 2)", "twice(4)", "nothing()", "add", "add == add"])
+      .expected(vec!["3", "8", "nil", "<native fn>", "true"])
+      .run()
+  }
+
+  #[test]
+  fn native_errors() -> Result {
+    let error = Test::new()
+      .native("number", 1, |_, arguments| match arguments {
+        [Value::Literal(Literal::Number(_))] => Ok(arguments[0].clone()),
+        _ => Err(Error::native("Argument must be a number.")),
+      })
+      .program("fun f() {\n  return number(\"one\");\n}\nf();")
+      .run()
+      .unwrap_err();
+
+    assert_eq!(
+      error.to_string(),
+      "Runtime Error: Argument must be a number. [line 2]"
+    );
+
+    let error = Test::new()
+      .native("unary", 1, |_, _| Ok(Value::Literal(Literal::Nil)))
+      .program("unary();")
+      .run()
+      .unwrap_err();
+
+    assert_eq!(
+      error.to_string(),
+      "Runtime Error: Expected 1 arguments but got 0. [line 1]"
+    );
+
+    Ok(())
+  }
+
+  #[test]
+  fn clock() -> Result {
+    Test::new()
+      .source(vec!["clock", "clock() > 0", "clock() <= clock()"])
+      .expected(vec!["<native fn>", "true", "true"])
+      .run()
+  }
+
+  #[test]
+  fn step_limit() -> Result {
+    let limits = Limits {
+      steps: Some(1000),
+      ..Limits::default()
+    };
+
+    Test::new()
+      .limits(limits.clone())
+      .program("for (var i = 0; i < 10; i = i + 1) {}")
+      .run()?;
+
+    let error = Test::new()
+      .limits(limits)
+      .program("var i = 0;\nwhile (true) i = i + 1;")
+      .run()
+      .unwrap_err();
+
+    assert_eq!(
+      error.to_string(),
+      "Runtime Error: Step limit exceeded. [line 2]"
+    );
+
+    Ok(())
+  }
+
+  #[test]
+  fn call_depth_limit() -> Result {
+    let limits = Limits {
+      call_depth: 10,
+      ..Limits::default()
+    };
+
+    let program = "fun f(n) { if (n > 0) f(n - 1); }";
+
+    Test::new()
+      .limits(limits.clone())
+      .program(&format!("{}\nf(9);", program))
+      .run()?;
+
+    let error = Test::new()
+      .limits(limits)
+      .program(&format!("{}\nf(10);", program))
+      .run()
+      .unwrap_err();
+
+    assert!(matches!(
+      error,
+      Error::Limit {
+        kind: LimitKind::CallDepth,
+        ..
+      }
+    ));
+
+    Ok(())
+  }
+
+  #[test]
+  fn long_instance_chain() -> Result {
+    Test::new()
+      .program(
+        "
+        class Node {}
+        var list = nil;
+        for (var i = 0; i < 100000; i = i + 1) {
+          var node = Node();
+          node.next = list;
+          list = node;
+        }
+        print \"built\";
+        ",
+      )
+      .printed(vec!["built"])
+      .run()
+  }
+
+  #[test]
+  fn native_stack_limit() {
+    let error = thread::Builder::new()
+      .stack_size(256 * 1024)
+      .spawn(|| {
+        Test::new()
+          .limits(Limits {
+            call_depth: usize::MAX,
+            ..Limits::default()
+          })
+          .program("fun f(n) { if (n > 0) f(n - 1); }\nf(100000);")
+          .run()
+          .unwrap_err()
+          .to_string()
+      })
+      .unwrap()
+      .join()
+      .unwrap();
+
+    assert_eq!(error, "Runtime Error: Stack overflow. [line 1]");
+  }
+
+  #[test]
+  fn budgets_per_run() -> Result {
+    let mut interpreter = Interpreter::with_limits(Limits {
+      heap: Some(1000),
+      steps: Some(1000),
+      ..Limits::default()
+    });
+
+    let program = Parser::parse(Lexer::tokens(
+      "for (var i = 0; i < 10; i = i + 1) { var s = \"${i}\"; }",
+    ))?;
+
+    Resolver::resolve(&program)?;
+
+    for _ in 0..100 {
+      interpreter.interpret(&program)?;
+    }
+
+    Ok(())
+  }
+
+  #[test]
+  fn string_length_limit() -> Result {
+    let limits = Limits {
+      string_length: Some(100),
+      ..Limits::default()
+    };
+
+    let error = Test::new()
+      .limits(limits.clone())
+      .program("var s = \"ab\";\nwhile (true) s = s + s;")
+      .run()
+      .unwrap_err();
+
+    assert_eq!(
+      error.to_string(),
+      "Runtime Error: String length limit exceeded. [line 2]"
+    );
+
+    let error = Test::new()
+      .limits(limits)
+      .program("var s = \"0123456789\";\nvar t = \"${s}${s}${s}${s}${s}${s}${s}${s}${s}${s}${s}\";")
+      .run()
+      .unwrap_err();
+
+    assert_eq!(
+      error.to_string(),
+      "Runtime Error: String length limit exceeded. [line 2]"
+    );
+
+    Ok(())
+  }
+
+  #[test]
+  fn heap_limit() -> Result {
+    let error = Test::new()
+      .limits(Limits {
+        heap: Some(100_000),
+        ..Limits::default()
+      })
+      .program(
+        "
+        class Node {}
+        var list = nil;
+        while (true) {
+          var node = Node();
+          node.next = list;
+          list = node;
+        }
+        ",
+      )
+      .run()
+      .unwrap_err();
+
+    assert!(matches!(
+      error,
+      Error::Limit {
+        kind: LimitKind::Heap,
+        ..
+      }
+    ));
+
+    Ok(())
+  }
+
+  #[test]
+  fn cancellation() {
+    let mut interpreter = Interpreter::new();
+
+    let handle = interpreter.cancel_handle();
+
+    let canceller = thread::spawn({
+      let handle = handle.clone();
+      move || {
+        thread::sleep(std::time::Duration::from_millis(10));
+        handle.cancel();
+      }
+    });
+
+    let program =
+      Parser::parse(Lexer::tokens("var spin = true;\nwhile (spin) {}"))
+        .unwrap();
+
+    let error = interpreter.interpret(&program).unwrap_err();
+
+    canceller.join().unwrap();
+
+    assert_eq!(
+      error.to_string(),
+      "Runtime Error: Execution cancelled. [line 2]"
+    );
+
+    assert!(interpreter.interpret(&program[..1]).is_err());
+
+    handle.reset();
+
+    interpreter.interpret(&program[..1]).unwrap();
+  }
 }
@@ -1,10 +1,18 @@
 use crate::common::*;
 
 #[derive(Debug)]
-pub(crate) struct Lexer<'src> {
+pub struct Lexer<'src> {
+  chars: Chars<'src>,
+  column: usize,
+  /// The number of unclosed `{` inside each interpolated expression we're in,
+  /// innermost last.
+  interpolations: Vec<usize>,
+  line: usize,
   position: Position,
   src: &'src str,
-  tokens: Vec<Token<'src>>,
+  tokens: Vec<Token>,
+  /// Whether the source ended inside a string or block comment.
+  unterminated: bool,
 }
 
 lazy_static! {
@@ -31,86 +39,178 @@ lazy_static! {
 }
 
 impl<'src> Lexer<'src> {
-  /// Lex `src`.
-  pub(crate) fn lex(src: &'src str) -> Result<Vec<Token<'src>>> {
+  /// Lex `src`, failing with every lexical error found if it contains any.
+  #[cfg(any(test, feature = "internals"))]
+  pub fn lex(src: &'src str) -> Result<Vec<Token>> {
+    let tokens = Lexer::tokens(src);
+
+    let errors = tokens
+      .iter()
+      .filter_map(Token::error)
+      .collect::<Vec<Error>>();
+
+    if !errors.is_empty() {
+      return Err(Error::Compile { errors });
+    }
+
+    Ok(tokens)
+  }
+
+  /// Lex `src` without stopping at lexical errors, which are recorded in the
+  /// returned stream as `TokenKind::Error` tokens for the parser to report.
+  pub fn tokens(src: &'src str) -> Vec<Token> {
     Lexer::new(src).tokenize()
   }
 
+  /// The reserved words of the language.
+  pub fn keywords() -> impl Iterator<Item = &'static str> {
+    KEYWORDS.keys().copied()
+  }
+
+  /// Whether `src` breaks off partway through something more input could
+  /// complete: an unclosed `(` or `{`, string, interpolated expression or
+  /// block comment.
+  pub fn is_incomplete(src: &'src str) -> bool {
+    let mut lexer = Lexer::new(src);
+
+    let tokens = lexer.tokenize();
+
+    if lexer.unterminated || !lexer.interpolations.is_empty() {
+      return true;
+    }
+
+    let mut depth = 0;
+
+    for token in tokens {
+      match token.kind {
+        BraceL | ParenL => depth += 1,
+        BraceR | ParenR => depth -= 1,
+        _ => {}
+      }
+    }
+
+    depth > 0
+  }
+
   /// Create and return a new `Lexer` instance based on `src`.
   fn new(src: &'src str) -> Self {
     Self {
+      chars: src.chars(),
+      column: 1,
+      interpolations: Vec::new(),
+      line: 1,
       position: Position {
-        start: 0,
+        column: 1,
         current: 0,
         line: 1,
+        source: 0,
+        start: 0,
       },
       src,
       tokens: Vec::new(),
+      unterminated: false,
     }
   }
 
   /// The main loop. Keep lexing tokens while we haven't reached the end of the
   /// source code.
-  fn tokenize(mut self) -> Result<Vec<Token<'src>>> {
+  fn tokenize(&mut self) -> Vec<Token> {
     while !self.is_end() {
-      self.position.start = self.position.current;
-      self.lex_token()?;
+      self.begin_token();
+
+      if let Err(error) = self.lex_token() {
+        self.tokens.push(Token {
+          kind: TokenKind::Error {
+            message: error.message(),
+            help: error.help().map(str::to_owned),
+          },
+          lexeme: Some(
+            self.src[self.position.start..self.position.current].into(),
+          ),
+          position: self.position.clone(),
+        });
+      }
     }
 
-    self.token(Eof)?;
+    self.begin_token();
 
-    Ok(self.tokens)
+    self.tokens.push(Token {
+      kind: Eof,
+      lexeme: None,
+      position: self.position.clone(),
+    });
+
+    mem::take(&mut self.tokens)
   }
 
-  /// Increment our current position and return the characters that resides at
-  /// that the previous position.
-  fn advance(&mut self) -> Result<char> {
-    self.position.current += 1;
-    self.prev()
+  /// Construct a lexer error spanning the token currently being lexed, with
+  /// an optional `help` note.
+  fn error(&self, message: &str, help: Option<&str>) -> Error {
+    Error::Lexer {
+      message: message.to_owned(),
+      position: self.position.clone(),
+      help: help.map(str::to_owned),
+    }
   }
 
-  /// Return the character that resides at the previous position.
-  fn prev(&self) -> Result<char> {
-    self
-      .src
-      .chars()
-      .nth(self.position.current - 1)
-      .ok_or_else(|| Error::Lexer {
-        message: "Lexer advanced passed end of line.".into(),
-      })
+  /// Start a new token at the cursor.
+  fn begin_token(&mut self) {
+    self.position = Position {
+      column: self.column,
+      current: self.position.current,
+      line: self.line,
+      source: self.position.source,
+      start: self.position.current,
+    };
+  }
+
+  /// Move past the character at our current position and return it, keeping
+  /// the line and column of the cursor up to date.
+  fn advance(&mut self) -> Result<char> {
+    let c = self
+      .chars
+      .next()
+      .ok_or_else(|| self.error("Lexer advanced passed end of line.", None))?;
+
+    self.position.current += c.len_utf8();
+
+    if c == '\n' {
+      self.line += 1;
+      self.column = 1;
+    } else {
+      self.column += 1;
+    }
+
+    Ok(c)
   }
 
   /// Return the character that resides at our current position without
   /// incrementing the current position.
   fn peek(&self) -> char {
-    if self.is_end() {
-      return '\0';
-    }
-    self.src.chars().nth(self.position.current).unwrap()
+    self.chars.clone().next().unwrap_or('\0')
   }
 
-  /// Return the character that resides at the position one over from our
-  /// current position.
+  /// Return the character that resides one character over from our current
+  /// position.
   fn peek_next(&self) -> char {
-    if self.position.current + 1 >= self.src.len() {
-      return '\0';
-    }
-    self.src.chars().nth(self.position.current + 1).unwrap()
+    let mut chars = self.chars.clone();
+    chars.next();
+    chars.next().unwrap_or('\0')
   }
 
-  /// Check if our current position is greater than the length of `src`.
+  /// Check if the cursor has consumed all of `src`.
   fn is_end(&self) -> bool {
-    self.position.current >= self.src.len()
+    self.chars.as_str().is_empty()
   }
 
   /// Check if a character is a digit.
   fn is_digit(&self, c: char) -> bool {
-    ('0'..='9').contains(&c)
+    c.is_ascii_digit()
   }
 
   /// Check if a character is a letter.
   fn is_alpha(&self, c: char) -> bool {
-    ('a'..='z').contains(&c) || ('A'..='Z').contains(&c) || c == '_'
+    c.is_ascii_alphabetic() || c == '_'
   }
 
   /// Check if a character is a letter or a number.
@@ -128,7 +228,7 @@ impl<'src> Lexer<'src> {
       return Ok(false);
     }
 
-    self.position.current += 1;
+    self.advance()?;
     Ok(true)
   }
 
@@ -143,9 +243,25 @@ impl<'src> Lexer<'src> {
       '+' => self.lex_single(Plus),
       ',' => self.lex_single(Comma),
       '-' => self.lex_single(Minus),
+      '.' => self.lex_single(Dot),
       ';' => self.lex_single(Semicolon),
-      '{' => self.lex_single(BraceL),
-      '}' => self.lex_single(BraceR),
+      '{' => {
+        if let Some(depth) = self.interpolations.last_mut() {
+          *depth += 1;
+        }
+        self.lex_single(BraceL)
+      }
+      '}' => match self.interpolations.last_mut() {
+        Some(0) => {
+          self.interpolations.pop();
+          self.lex_string_literal()
+        }
+        Some(depth) => {
+          *depth -= 1;
+          self.lex_single(BraceR)
+        }
+        None => self.lex_single(BraceR),
+      },
       '!' => self.lex_choice('=', (BangEqual, Bang)),
       '<' => self.lex_choice('=', (LessEqual, Less)),
       '=' => self.lex_choice('=', (EqualEqual, Equal)),
@@ -160,20 +276,14 @@ impl<'src> Lexer<'src> {
           self.lex_single(Slash)
         }
       }
-      '\n' => {
-        self.position.line += 1;
-        Ok(())
-      }
-      ' ' | '\t' | '\r' => Ok(()),
+      ' ' | '\t' | '\r' | '\n' => Ok(()),
       _ => {
         if self.is_digit(start) {
-          self.lex_number()
+          self.lex_number(start)
         } else if self.is_alpha(start) {
           self.lex_ident()
         } else {
-          Err(Error::Lexer {
-            message: format!("Unexpected character: {}.", start),
-          })
+          Err(self.error("Unexpected character.", None))
         }
       }
     }
@@ -212,63 +322,285 @@ impl<'src> Lexer<'src> {
   fn lex_block_comment(&mut self) -> Result<()> {
     while !self.is_end() {
       if self.peek() == '*' && self.peek_next() == '/' {
-        self.position.current += 2;
+        self.advance()?;
+        self.advance()?;
         return Ok(());
       }
-      if self.peek() == '\n' {
-        self.position.line += 1;
-      }
       self.advance()?;
     }
 
     if self.is_end() {
-      return Err(Error::Lexer {
-        message: "Unterminated block comment.".into(),
-      });
+      self.unterminated = true;
+      return Err(self.error(
+        "Unterminated block comment.",
+        Some("Block comments must be closed with `*/`."),
+      ));
     }
 
     Ok(())
   }
 
-  /// Lex a string literal.
+  /// Lex a string literal, or the segment of one that follows an interpolated
+  /// expression.
+  ///
+  /// A segment ending in `${` is emitted as an `Interpolation` token, and the
+  /// rest of the string is picked up again once the matching `}` is lexed.
   fn lex_string_literal(&mut self) -> Result<()> {
-    while self.peek() != '"' && !self.is_end() {
-      if self.peek() == '\n' {
-        self.position.line += 1;
+    let mut invalid = None;
+
+    loop {
+      match self.peek() {
+        _ if self.is_end() => {
+          self.unterminated = true;
+          return Err(self.error(
+            "Unterminated string.",
+            Some("Strings must be closed with a matching `\"`."),
+          ));
+        }
+        '"' => {
+          self.advance()?;
+          break;
+        }
+        '$' if self.peek_next() == '{' => {
+          self.advance()?;
+          self.advance()?;
+          self.interpolations.push(0);
+          return self.token(Interpolation);
+        }
+        '\\' => {
+          if let Err(error) = self.lex_escape() {
+            invalid.get_or_insert(error);
+          }
+        }
+        _ => {
+          self.advance()?;
+        }
       }
-      self.advance()?;
     }
 
-    if self.is_end() {
-      return Err(Error::Lexer {
-        message: "Unterminated string.".into(),
-      });
+    match invalid {
+      Some(error) => Err(error),
+      None => self.token(StringLiteral),
     }
+  }
+
+  /// Check the escape sequence at the cursor, which must be one of the ones
+  /// `unescape` understands.
+  fn lex_escape(&mut self) -> Result<()> {
+    let position = Position {
+      column: self.column,
+      current: self.position.current,
+      line: self.line,
+      source: self.position.source,
+      start: self.position.current,
+    };
 
     self.advance()?;
 
-    self.token(StringLiteral)
+    let valid = match self.peek() {
+      'n' | 't' | '"' | '\\' | '$' => {
+        self.advance()?;
+        true
+      }
+      'u' => {
+        self.advance()?;
+        self.lex_unicode_escape()?
+      }
+      _ => false,
+    };
+
+    if valid {
+      return Ok(());
+    }
+
+    Err(Error::Lexer {
+      message: "Invalid escape sequence.".into(),
+      position: Position {
+        current: self.position.current,
+        ..position
+      },
+      help: Some(
+        "Valid escapes are `\\n`, `\\t`, `\\\"`, `\\\\`, `\\$` and `\\u{...}`."
+          .into(),
+      ),
+    })
   }
 
-  /// Lex a number.
-  fn lex_number(&mut self) -> Result<()> {
-    while self.is_digit(self.peek()) {
-      self.advance()?;
+  /// Consume the `{...}` part of a `\u{...}` escape, returning whether it
+  /// names a valid character.
+  fn lex_unicode_escape(&mut self) -> Result<bool> {
+    if self.peek() != '{' {
+      return Ok(false);
     }
 
-    // Look for the decimal
-    if self.peek() == '.' && self.is_digit(self.peek_next()) {
-      // Consume the `.`
+    self.advance()?;
+
+    let start = self.position.current;
+
+    while self.peek().is_ascii_hexdigit() {
       self.advance()?;
-      // Get the rest of the number
-      while self.is_digit(self.peek()) {
+    }
+
+    let digits = &self.src[start..self.position.current];
+
+    if self.peek() != '}' {
+      return Ok(false);
+    }
+
+    self.advance()?;
+
+    Ok(
+      digits.len() <= 6
+        && u32::from_str_radix(digits, 16)
+          .ok()
+          .and_then(char::from_u32)
+          .is_some(),
+    )
+  }
+
+  /// Decode the escape sequences in `raw`, the contents of a string literal
+  /// the lexer has already checked.
+  pub(crate) fn unescape(raw: &str) -> String {
+    let mut value = String::with_capacity(raw.len());
+
+    let mut chars = raw.chars();
+
+    while let Some(c) = chars.next() {
+      if c != '\\' {
+        value.push(c);
+        continue;
+      }
+
+      match chars.next() {
+        Some('n') => value.push('\n'),
+        Some('t') => value.push('\t'),
+        Some('u') => {
+          let rest = chars.as_str();
+
+          let digits = rest
+            .strip_prefix('{')
+            .and_then(|rest| rest.split_once('}'))
+            .map(|(digits, _)| digits)
+            .unwrap_or_default();
+
+          value.extend(
+            u32::from_str_radix(digits, 16)
+              .ok()
+              .and_then(char::from_u32),
+          );
+
+          chars = rest[(digits.len() + 2).min(rest.len())..].chars();
+        }
+        Some(c) => value.push(c),
+        None => {}
+      }
+    }
+
+    value
+  }
+
+  /// Lex a number given its first digit: a `0x` hexadecimal or `0b` binary
+  /// integer, or a decimal with an optional fraction and exponent. Digits may
+  /// be separated by underscores.
+  fn lex_number(&mut self, start: char) -> Result<()> {
+    let radix = match (start, self.peek()) {
+      ('0', 'x' | 'X') => 16,
+      ('0', 'b' | 'B') => 2,
+      _ => 10,
+    };
+
+    if radix == 10 {
+      self.lex_digits(radix)?;
+
+      // Look for the decimal
+      if self.peek() == '.' && self.is_digit(self.peek_next()) {
+        // Consume the `.`
+        self.advance()?;
+        // Get the rest of the number
+        self.lex_digits(radix)?;
+      }
+
+      if matches!(self.peek(), 'e' | 'E') {
+        self.advance()?;
+
+        if matches!(self.peek(), '+' | '-') {
+          self.advance()?;
+        }
+
+        if !self.lex_digits(radix)? {
+          return Err(self.error("Expect digits in exponent.", None));
+        }
+      }
+    } else {
+      let prefix = self.advance()?;
+
+      if !self.lex_digits(radix)? {
+        return Err(self.error(
+          &format!(
+            "Expect {} digits after '0{}'.",
+            if radix == 16 { "hexadecimal" } else { "binary" },
+            prefix
+          ),
+          None,
+        ));
+      }
+    }
+
+    if self.is_alphanumeric(self.peek()) {
+      while self.is_alphanumeric(self.peek()) {
         self.advance()?;
       }
+
+      return Err(self.error("Invalid digit in number literal.", None));
+    }
+
+    let lexeme = &self.src[self.position.start..self.position.current];
+
+    let misplaced_separator = lexeme.char_indices().any(|(i, c)| {
+      c == '_'
+        && !(lexeme[..i].ends_with(|c: char| c.is_digit(radix))
+          && lexeme[i + 1..].starts_with(|c: char| c.is_digit(radix)))
+    });
+
+    if misplaced_separator {
+      return Err(self.error(
+        "Misplaced '_' in number literal.",
+        Some("Underscores may only appear between digits."),
+      ));
     }
 
     self.token(Number)
   }
 
+  /// Consume a run of digits in `radix` and `_` separators, returning whether
+  /// there were any digits.
+  fn lex_digits(&mut self, radix: u32) -> Result<bool> {
+    let mut digits = false;
+
+    while self.peek().is_digit(radix) || self.peek() == '_' {
+      digits |= self.advance()? != '_';
+    }
+
+    Ok(digits)
+  }
+
+  /// The value of a number literal the lexer has already checked.
+  pub(crate) fn number(lexeme: &str) -> Result<f64> {
+    let digits = lexeme.replace('_', "");
+
+    let (radix, digits) = match digits.get(..2) {
+      Some("0x" | "0X") => (16, &digits[2..]),
+      Some("0b" | "0B") => (2, &digits[2..]),
+      _ => return Ok(digits.parse()?),
+    };
+
+    // Accumulate in floating point so oversized literals lose precision
+    // rather than overflowing.
+    Ok(digits.chars().fold(0.0, |value, digit| {
+      value * f64::from(radix) + f64::from(digit.to_digit(radix).unwrap_or(0))
+    }))
+  }
+
   /// Lex an identifier.
   fn lex_ident(&mut self) -> Result<()> {
     while self.is_alphanumeric(self.peek()) {
@@ -291,13 +623,16 @@ impl<'src> Lexer<'src> {
       StringLiteral => {
         Some(&self.src[self.position.start + 1..self.position.current - 1])
       }
+      Interpolation => {
+        Some(&self.src[self.position.start + 1..self.position.current - 2])
+      }
       Eof => None,
       _ => Some(&self.src[self.position.start..self.position.current]),
     };
 
     self.tokens.push(Token {
       kind,
-      lexeme,
+      lexeme: lexeme.map(Rc::from),
       position: self.position.clone(),
     });
 
@@ -307,7 +642,11 @@ impl<'src> Lexer<'src> {
 
 #[cfg(test)]
 mod tests {
-  use {super::*, pretty_assertions::assert_eq};
+  use {
+    super::*,
+    pretty_assertions::assert_eq,
+    std::time::{Duration, Instant},
+  };
 
   struct Test<'a> {
     source: String,
@@ -334,13 +673,14 @@ mod tests {
     }
 
     fn run(&self) -> Result {
-      Ok(assert_eq!(
+      assert_eq!(
         Lexer::lex(&self.source)?
           .iter()
-          .map(|token| (token.to_owned().kind, token.lexeme))
+          .map(|token| (token.to_owned().kind, token.lexeme()))
           .collect::<Vec<(TokenKind, Option<&str>)>>(),
         self.expected
-      ))
+      );
+      Ok(())
     }
   }
 
@@ -646,4 +986,303 @@ mod tests {
       ])
       .run()
   }
+
+  #[test]
+  fn property_access() -> Result {
+    Test::new()
+      .source("foo.bar = this.baz;")
+      .expected(vec![
+        (Identifier, Some("foo")),
+        (Dot, Some(".")),
+        (Identifier, Some("bar")),
+        (Equal, Some("=")),
+        (This, Some("this")),
+        (Dot, Some(".")),
+        (Identifier, Some("baz")),
+        (Semicolon, Some(";")),
+        (Eof, None),
+      ])
+      .run()
+  }
+
+  #[test]
+  fn unicode() -> Result {
+    Test::new()
+      .source("// ünïcödé\nprint \"日本語\" + \"🦀\";")
+      .expected(vec![
+        (Print, Some("print")),
+        (StringLiteral, Some("日本語")),
+        (Plus, Some("+")),
+        (StringLiteral, Some("🦀")),
+        (Semicolon, Some(";")),
+        (Eof, None),
+      ])
+      .run()
+  }
+
+  #[test]
+  fn positions() -> Result {
+    assert_eq!(
+      Lexer::lex("\"é\"\n  a /* ✓\n */ b")?
+        .iter()
+        .map(|token| token.position.clone())
+        .collect::<Vec<Position>>(),
+      vec![
+        Position {
+          column: 1,
+          current: 4,
+          line: 1,
+          source: 0,
+          start: 0,
+        },
+        Position {
+          column: 3,
+          current: 8,
+          line: 2,
+          source: 0,
+          start: 7,
+        },
+        Position {
+          column: 5,
+          current: 21,
+          line: 3,
+          source: 0,
+          start: 20,
+        },
+        Position {
+          column: 6,
+          current: 21,
+          line: 3,
+          source: 0,
+          start: 21,
+        },
+      ]
+    );
+
+    Ok(())
+  }
+
+  #[test]
+  fn error_recovery() {
+    assert_eq!(
+      Lexer::tokens("a @ b # \"c")
+        .iter()
+        .map(|token| (token.kind.clone(), token.lexeme()))
+        .collect::<Vec<(TokenKind, Option<&str>)>>(),
+      vec![
+        (Identifier, Some("a")),
+        (
+          TokenKind::Error {
+            message: "Unexpected character.".into(),
+            help: None
+          },
+          Some("@")
+        ),
+        (Identifier, Some("b")),
+        (
+          TokenKind::Error {
+            message: "Unexpected character.".into(),
+            help: None
+          },
+          Some("#")
+        ),
+        (
+          TokenKind::Error {
+            message: "Unterminated string.".into(),
+            help: Some("Strings must be closed with a matching `\"`.".into())
+          },
+          Some("\"c")
+        ),
+        (Eof, None),
+      ]
+    );
+  }
+
+  #[test]
+  fn errors() {
+    assert_eq!(
+      Lexer::lex("@\n#").unwrap_err().to_string(),
+      "Lexer Error: Unexpected character. [line 1]\n\
+       Lexer Error: Unexpected character. [line 2]"
+    );
+  }
+
+  #[test]
+  fn escapes() -> Result {
+    Test::new()
+      .source(r#""a\n\"b\"" "\u{1F980}""#)
+      .expected(vec![
+        (StringLiteral, Some(r#"a\n\"b\""#)),
+        (StringLiteral, Some(r"\u{1F980}")),
+        (Eof, None),
+      ])
+      .run()
+  }
+
+  #[test]
+  fn unescape() {
+    assert_eq!(
+      Lexer::unescape(r#"\n\t\"\\\$\u{48}\u{1f980}!"#),
+      "\n\t\"\\$H🦀!"
+    );
+  }
+
+  #[test]
+  fn invalid_escapes() {
+    assert_eq!(
+      Lexer::lex(r#""\q" "\u{110000}" "\u{zz}" "\é""#)
+        .unwrap_err()
+        .to_string(),
+      "Lexer Error: Invalid escape sequence. [line 1]\n\
+       Lexer Error: Invalid escape sequence. [line 1]\n\
+       Lexer Error: Invalid escape sequence. [line 1]\n\
+       Lexer Error: Invalid escape sequence. [line 1]"
+    );
+  }
+
+  #[test]
+  fn interpolation() -> Result {
+    Test::new()
+      .source(r#""a ${b + "${c}"} { ${ {} } d""#)
+      .expected(vec![
+        (Interpolation, Some("a ")),
+        (Identifier, Some("b")),
+        (Plus, Some("+")),
+        (Interpolation, Some("")),
+        (Identifier, Some("c")),
+        (StringLiteral, Some("")),
+        (Interpolation, Some(" { ")),
+        (BraceL, Some("{")),
+        (BraceR, Some("}")),
+        (StringLiteral, Some(" d")),
+        (Eof, None),
+      ])
+      .run()
+  }
+
+  #[test]
+  fn number_formats() -> Result {
+    Test::new()
+      .source("0x1F 0XfF 0b1010 1_000_000 1.5e-3 2E+10 12.5.abs")
+      .expected(vec![
+        (Number, Some("0x1F")),
+        (Number, Some("0XfF")),
+        (Number, Some("0b1010")),
+        (Number, Some("1_000_000")),
+        (Number, Some("1.5e-3")),
+        (Number, Some("2E+10")),
+        (Number, Some("12.5")),
+        (Dot, Some(".")),
+        (Identifier, Some("abs")),
+        (Eof, None),
+      ])
+      .run()
+  }
+
+  #[test]
+  fn number_values() -> Result {
+    assert_eq!(
+      [
+        "0x1F",
+        "0b1010",
+        "1_000_000",
+        "1.5e-3",
+        "2E+10",
+        "0xFFFF_FFFF"
+      ]
+      .iter()
+      .map(|lexeme| Lexer::number(lexeme))
+      .collect::<Result<Vec<f64>>>()?,
+      vec![31.0, 10.0, 1_000_000.0, 0.0015, 2e10, 4_294_967_295.0]
+    );
+
+    Ok(())
+  }
+
+  #[test]
+  fn malformed_numbers() {
+    assert_eq!(
+      Lexer::tokens("0x 0b2 1e 1e+ 1_ 1__0 0x_1 12abc")
+        .iter()
+        .filter_map(|token| match &token.kind {
+          TokenKind::Error { message, .. } => {
+            Some((message.as_str(), token.lexeme()))
+          }
+          _ => None,
+        })
+        .collect::<Vec<(&str, Option<&str>)>>(),
+      vec![
+        ("Expect hexadecimal digits after '0x'.", Some("0x")),
+        ("Expect binary digits after '0b'.", Some("0b")),
+        ("Expect digits in exponent.", Some("1e")),
+        ("Expect digits in exponent.", Some("1e+")),
+        ("Misplaced '_' in number literal.", Some("1_")),
+        ("Misplaced '_' in number literal.", Some("1__0")),
+        ("Misplaced '_' in number literal.", Some("0x_1")),
+        ("Invalid digit in number literal.", Some("12abc")),
+      ]
+    );
+  }
+
+  #[test]
+  fn incomplete() {
+    for src in [
+      "fun f() {",
+      "print (1 +",
+      "class A {\n  f() {\n    return 1;\n  }",
+      "print \"abc",
+      "print \"a ${1 +",
+      "print \"a ${1} b",
+      "/* comment",
+    ] {
+      assert!(Lexer::is_incomplete(src), "{:?} should be incomplete", src);
+    }
+
+    for src in [
+      "",
+      "print 1;",
+      "fun f() {}",
+      "print \"a ${\"b\"} c\";",
+      "/* comment */",
+      "print 1 +",
+      "}",
+      "print @;",
+    ] {
+      assert!(!Lexer::is_incomplete(src), "{:?} should be complete", src);
+    }
+  }
+
+  /// Lex progressively larger sources, checking that the time taken grows
+  /// linearly with their size. Run with `just bench`.
+  #[test]
+  #[ignore]
+  fn linear_time() -> Result {
+    let line = "var cafe = \"ünïcödé\" + 12.5; // comment\n";
+
+    let time = |lines: usize| -> Result<Duration> {
+      let src = line.repeat(lines);
+      let start = Instant::now();
+      Lexer::lex(&src)?;
+      Ok(start.elapsed())
+    };
+
+    let small = time(10_000)?;
+    let large = time(80_000)?;
+
+    eprintln!(
+      "lexed {} bytes in {:?}, {} bytes in {:?}",
+      line.len() * 10_000,
+      small,
+      line.len() * 80_000,
+      large
+    );
+
+    assert!(
+      large < small * 16,
+      "lexing 8x the source took {:.1}x as long",
+      large.as_secs_f64() / small.as_secs_f64()
+    );
+
+    Ok(())
+  }
 }
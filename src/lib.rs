@@ -0,0 +1,72 @@
+//! An interpreter for the [Lox](https://craftinginterpreters.com/) programming
+//! language.
+//!
+//! The simplest way to embed it is through [`Lox`], which keeps global state
+//! between runs:
+//!
+//! ```
+//! use lox::{Literal, Lox, Value};
+//!
+//! let mut lox = Lox::new();
+//!
+//! lox.run("var greeting = \"hello\";")?;
+//!
+//! let value = lox.run("greeting + \" world\";")?;
+//!
+//! assert_eq!(value, Some(Value::from(Literal::String("hello world".into()))));
+//! # Ok::<(), lox::Error>(())
+//! ```
+//!
+//! The individual passes, `Lexer`, `Parser` and `Resolver`, along with the
+//! tokens and syntax trees they work with, are exported by the `internals`
+//! feature for tools that need them. They aren't covered by the stability of
+//! the rest of the API.
+
+pub use crate::{
+  callable::Callable, cancel_handle::CancelHandle, class::Class,
+  common::Result, diagnostic::Diagnostic, error::Error, function::Function,
+  instance::Instance, interpreter::Interpreter, limit_kind::LimitKind,
+  limits::Limits, literal::Literal, lox::Lox, native::Native,
+  position::Position, value::Value,
+};
+
+#[cfg(feature = "internals")]
+pub use crate::{
+  depth::Depth, expr::Expr, function_declaration::FunctionDeclaration,
+  lexer::Lexer, parser::Parser, printer::Printer, resolver::Resolver,
+  stmt::Stmt, token::Token, token_kind::TokenKind,
+};
+
+mod callable;
+mod cancel_handle;
+mod class;
+mod class_kind;
+mod common;
+mod depth;
+mod diagnostic;
+mod environment;
+mod error;
+mod expr;
+mod function;
+mod function_declaration;
+mod function_kind;
+mod instance;
+mod interpreter;
+mod lexer;
+mod limit_kind;
+mod limits;
+mod literal;
+mod lox;
+mod native;
+mod parser;
+mod position;
+#[cfg(feature = "internals")]
+mod printer;
+mod resolver;
+mod stmt;
+#[cfg(test)]
+mod testing;
+mod token;
+mod token_kind;
+mod value;
+mod visitor;
@@ -0,0 +1,36 @@
+use crate::common::*;
+
+/// The sandbox limit a script ran into.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LimitKind {
+  /// Calls nested deeper than `Limits::call_depth`.
+  CallDepth,
+  /// The script was stopped through a `CancelHandle`.
+  Cancelled,
+  /// More memory allocated than `Limits::heap`.
+  Heap,
+  /// The host thread's native stack ran low, while running the script or
+  /// while parsing or resolving source that is nested too deeply.
+  Stack,
+  /// More statements and expressions evaluated than `Limits::steps`.
+  Steps,
+  /// A string built longer than `Limits::string_length`.
+  StringLength,
+}
+
+impl Display for LimitKind {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    write!(
+      f,
+      "{}",
+      match self {
+        LimitKind::CallDepth => "Stack overflow.",
+        LimitKind::Cancelled => "Execution cancelled.",
+        LimitKind::Heap => "Heap limit exceeded.",
+        LimitKind::Stack => "Stack overflow.",
+        LimitKind::Steps => "Step limit exceeded.",
+        LimitKind::StringLength => "String length limit exceeded.",
+      }
+    )
+  }
+}
@@ -0,0 +1,65 @@
+use crate::common::*;
+
+/// Bounds on the resources a script may use, for running untrusted code.
+/// Exceeding one fails the script with an `Error::Limit` naming it. Steps and
+/// heap usage are counted afresh for each script run, so a long-lived
+/// interpreter can keep running scripts that each stay within them.
+///
+/// ```
+/// use lox::{Error, LimitKind, Limits, Lox};
+///
+/// let limits = Limits {
+///   steps: Some(10_000),
+///   ..Limits::default()
+/// };
+///
+/// let mut lox = Lox::with_limits(limits);
+///
+/// assert!(matches!(
+///   lox.run("while (true) {}"),
+///   Err(Error::Limit { kind: LimitKind::Steps, .. })
+/// ));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Limits {
+  /// The maximum number of nested calls. Whatever this is set to, a script
+  /// that is about to exhaust the native stack fails with
+  /// `LimitKind::Stack` instead.
+  pub call_depth: usize,
+  /// The maximum number of bytes the script may allocate for strings,
+  /// instances, variables and call frames, approximately, in a single run.
+  /// Memory freed during the run isn't credited back.
+  pub heap: Option<usize>,
+  /// The maximum number of statements and expressions evaluated.
+  pub steps: Option<u64>,
+  /// The maximum length in bytes of a string built at runtime.
+  pub string_length: Option<usize>,
+}
+
+impl Limits {
+  /// Deep enough for reasonable recursion, while stopping well before the
+  /// host's own stack runs out.
+  pub const DEFAULT_CALL_DEPTH: usize = 255;
+
+  /// The native stack that must be left free whenever the parser, resolver
+  /// or interpreter recurses, comfortably more than any of them uses between
+  /// two checks.
+  const STACK_RED_ZONE: usize = 128 * 1024;
+
+  /// Whether the native stack is close enough to running out that recursing
+  /// any further could overflow it, whatever the other limits are.
+  pub(crate) fn stack_exhausted() -> bool {
+    remaining_stack().is_some_and(|remaining| remaining < Self::STACK_RED_ZONE)
+  }
+}
+
+impl Default for Limits {
+  fn default() -> Self {
+    Self {
+      call_depth: Self::DEFAULT_CALL_DEPTH,
+      heap: None,
+      steps: None,
+      string_length: None,
+    }
+  }
+}
@@ -1,7 +1,7 @@
 use crate::common::*;
 
 #[derive(Debug, PartialEq, Clone)]
-pub(crate) enum Literal {
+pub enum Literal {
   Boolean(bool),
   Nil,
   Number(f64),
@@ -17,9 +17,102 @@ impl Display for Literal {
         Literal::Boolean(value) =>
           String::from(if *value { "true" } else { "false" }),
         Literal::Nil => "nil".to_string(),
-        Literal::Number(value) => (*value).to_string(),
+        Literal::Number(value) => Self::format_number(*value),
         Literal::String(value) => value.to_string(),
       }
     )
   }
 }
+
+impl Literal {
+  /// Format `value` the way the reference implementation does: Java's
+  /// `Double.toString`, with a trailing `.0` dropped so integers print
+  /// without a fractional part.
+  fn format_number(value: f64) -> String {
+    if value.is_nan() {
+      return "NaN".into();
+    }
+
+    let sign = if value.is_sign_negative() { "-" } else { "" };
+
+    if value.is_infinite() {
+      return format!("{}Infinity", sign);
+    }
+
+    if value == 0.0 {
+      return format!("{}0", sign);
+    }
+
+    // Rust's exponential formatting gives the shortest digits that round trip,
+    // which is what Java prints as well.
+    let scientific = format!("{:e}", value.abs());
+
+    let (mantissa, exponent) = scientific.split_once('e').unwrap_or_default();
+
+    let digits = mantissa.replace('.', "");
+
+    let exponent = exponent.parse::<i32>().unwrap_or_default();
+
+    let formatted = if (1e-3..1e7).contains(&value.abs()) {
+      let point = exponent + 1;
+
+      if point <= 0 {
+        format!("0.{}{}", "0".repeat(point.unsigned_abs() as usize), digits)
+      } else if point as usize >= digits.len() {
+        format!("{}{}.0", digits, "0".repeat(point as usize - digits.len()))
+      } else {
+        let (whole, fraction) = digits.split_at(point as usize);
+        format!("{}.{}", whole, fraction)
+      }
+    } else {
+      let (first, rest) = digits.split_at(1);
+      format!(
+        "{}.{}E{}",
+        first,
+        if rest.is_empty() { "0" } else { rest },
+        exponent
+      )
+    };
+
+    let formatted = formatted.strip_suffix(".0").unwrap_or(&formatted);
+
+    format!("{}{}", sign, formatted)
+  }
+
+  /// Lox treats `nil` and `false` as falsey and every other value as truthy.
+  pub fn is_truthy(&self) -> bool {
+    !matches!(self, Literal::Nil | Literal::Boolean(false))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use {super::*, pretty_assertions::assert_eq};
+
+  #[test]
+  fn numbers() {
+    let cases = [
+      (0.0, "0"),
+      (-0.0, "-0"),
+      (1.0, "1"),
+      (-3.0, "-3"),
+      (123.456, "123.456"),
+      (0.1 + 0.2, "0.30000000000000004"),
+      (0.001, "0.001"),
+      (0.0001, "1.0E-4"),
+      (1.5e-7, "1.5E-7"),
+      (9_999_999.0, "9999999"),
+      (10_000_000.0, "1.0E7"),
+      (1e21, "1.0E21"),
+      (123_456_789.0, "1.23456789E8"),
+      (f64::MAX, "1.7976931348623157E308"),
+      (f64::INFINITY, "Infinity"),
+      (f64::NEG_INFINITY, "-Infinity"),
+      (f64::NAN, "NaN"),
+    ];
+
+    for (value, expected) in cases {
+      assert_eq!(Literal::Number(value).to_string(), expected);
+    }
+  }
+}
@@ -0,0 +1,334 @@
+use crate::common::*;
+
+/// A Lox session: source code run through it shares one set of globals, so
+/// later runs can use what earlier ones defined.
+#[derive(Debug, Default)]
+pub struct Lox {
+  interpreter: Interpreter,
+  /// Every source run so far, if the session was asked to keep them.
+  sources: Option<Vec<String>>,
+}
+
+impl Lox {
+  /// Construct and return a new `Lox` session with no globals defined.
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Construct a session that fails scripts exceeding `limits`.
+  pub fn with_limits(limits: Limits) -> Self {
+    Self {
+      interpreter: Interpreter::with_limits(limits),
+      sources: None,
+    }
+  }
+
+  /// Send the output of `print` statements to `output`, as with
+  /// `Interpreter::output`.
+  pub fn output(self, output: impl Write + 'static) -> Self {
+    Self {
+      interpreter: self.interpreter.output(output),
+      ..self
+    }
+  }
+
+  /// Keep every source run in this session, so that errors can be rendered
+  /// against the source they point into with `Lox::source`, even when they
+  /// come from code run earlier, like the body of a function being called.
+  /// The sources are held for as long as the session lives.
+  pub fn keep_sources(self) -> Self {
+    Self {
+      sources: Some(Vec::new()),
+      ..self
+    }
+  }
+
+  /// The names of every global defined in this session.
+  pub fn globals(&self) -> Vec<String> {
+    self.interpreter.globals()
+  }
+
+  /// The value of the global `name`, if this session has defined it.
+  pub fn global(&self, name: &str) -> Option<Value> {
+    self.interpreter.global(name)
+  }
+
+  /// The source with index `source` among those run in this session, which
+  /// errors from it can be rendered against. See `Position::source`. Sources
+  /// are only available if the session keeps them.
+  pub fn source(&self, source: usize) -> Option<&str> {
+    self.sources.as_ref()?.get(source).map(String::as_str)
+  }
+
+  /// Whether `source` breaks off partway through something more input could
+  /// complete, like an unclosed block or string, so a prompt should read
+  /// another line before running it.
+  pub fn is_incomplete(source: &str) -> bool {
+    Lexer::is_incomplete(source)
+  }
+
+  /// The reserved words of the language.
+  pub fn keywords() -> impl Iterator<Item = &'static str> {
+    Lexer::keywords()
+  }
+
+  /// A handle for cancelling scripts run in this session from another thread.
+  pub fn cancel_handle(&self) -> CancelHandle {
+    self.interpreter.cancel_handle()
+  }
+
+  /// Install `function` as a global callable, as with
+  /// `Interpreter::define_native`.
+  pub fn define_native(
+    &mut self,
+    name: &str,
+    arity: usize,
+    function: impl Fn(&mut Interpreter, &[Value]) -> Result<Value> + 'static,
+  ) {
+    self.interpreter.define_native(name, arity, function);
+  }
+
+  /// Lex, parse, resolve and execute `source`, reporting every syntax error
+  /// in it rather than just the first.
+  ///
+  /// If the last statement is an expression statement its value is returned.
+  /// Otherwise the result is `None`.
+  pub fn run(&mut self, source: &str) -> Result<Option<Value>> {
+    let tokens = self.lex(source);
+
+    self.execute(tokens)
+  }
+
+  /// Run a line typed at a prompt. Unlike `run`, this also accepts a lone
+  /// expression without a trailing `;`, returning its value.
+  pub fn eval_line(&mut self, source: &str) -> Result<Option<Value>> {
+    let mut tokens = self.lex(source);
+
+    if Self::is_expression(&tokens) {
+      let eof = tokens.len() - 1;
+
+      tokens.insert(
+        eof,
+        Token {
+          kind: Semicolon,
+          lexeme: Some(";".into()),
+          position: tokens[eof].position.clone(),
+        },
+      );
+    }
+
+    self.execute(tokens)
+  }
+
+  /// Lex `source`. If the session keeps its sources, hold on to it and mark
+  /// its tokens with its index so errors can later be traced back to it.
+  fn lex(&mut self, source: &str) -> Vec<Token> {
+    let mut tokens = Lexer::tokens(source);
+
+    if let Some(sources) = &mut self.sources {
+      for token in &mut tokens {
+        token.position.source = sources.len();
+      }
+
+      sources.push(source.to_owned());
+    }
+
+    tokens
+  }
+
+  /// Parse, resolve and execute `tokens`, returning the value of the last
+  /// statement if it's an expression statement.
+  fn execute(&mut self, tokens: Vec<Token>) -> Result<Option<Value>> {
+    let statements = Parser::parse(tokens)?;
+
+    Resolver::resolve(&statements)?;
+
+    match statements.split_last() {
+      Some((Stmt::Expression { expression }, rest)) => {
+        self.interpreter.interpret(rest)?;
+        self.interpreter.eval(expression).map(Some)
+      }
+      _ => {
+        self.interpreter.interpret(&statements)?;
+        Ok(None)
+      }
+    }
+  }
+
+  /// Whether `tokens` end without the `;` or `}` that closes a statement, in
+  /// which case they're taken to be a bare expression.
+  fn is_expression(tokens: &[Token]) -> bool {
+    match tokens.iter().rev().nth(1) {
+      Some(token) => {
+        !matches!(token.kind, Semicolon | BraceR | TokenKind::Error { .. })
+      }
+      None => false,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use {super::*, crate::testing::Buffer, pretty_assertions::assert_eq};
+
+  fn display(value: Option<Value>) -> Option<String> {
+    value.map(|value| value.to_string())
+  }
+
+  #[test]
+  fn expression_value() -> Result {
+    let mut lox = Lox::new();
+
+    assert_eq!(display(lox.run("1 + 2;")?), Some("3".into()));
+    assert_eq!(display(lox.run("\"a\" + \"b\";")?), Some("ab".into()));
+    assert_eq!(lox.run("var a = 1;")?, None);
+    assert_eq!(lox.run("")?, None);
+
+    Ok(())
+  }
+
+  #[test]
+  fn eval_line() -> Result {
+    let mut lox = Lox::new();
+
+    assert_eq!(display(lox.eval_line("1 + 2")?), Some("3".into()));
+    assert_eq!(lox.eval_line("var a = 1")?, None);
+    assert_eq!(display(lox.eval_line("a")?), Some("1".into()));
+    assert_eq!(lox.eval_line("fun f() {}")?, None);
+    assert_eq!(lox.eval_line("var x;")?, None);
+    assert_eq!(display(lox.eval_line("x")?), Some("nil".into()));
+    assert_eq!(display(lox.eval_line("nil")?), Some("nil".into()));
+
+    Ok(())
+  }
+
+  #[test]
+  fn persistent_globals() -> Result {
+    let mut lox = Lox::new();
+
+    lox.run("var count = 0; fun bump() { count = count + 1; }")?;
+    lox.run("bump(); bump();")?;
+
+    assert_eq!(display(lox.run("count;")?), Some("2".into()));
+
+    lox.run("class A { f() { return \"f\"; } }")?;
+
+    assert_eq!(display(lox.run("A().f();")?), Some("f".into()));
+
+    Ok(())
+  }
+
+  #[test]
+  fn sources() -> Result {
+    let mut lox = Lox::new().keep_sources();
+
+    lox.eval_line("fun f() { return -\"x\"; }")?;
+
+    let error = lox.eval_line("var s = \"é\"; f()").unwrap_err();
+
+    let position = error.position().unwrap();
+
+    assert_eq!(
+      lox.source(position.source),
+      Some("fun f() { return -\"x\"; }")
+    );
+
+    assert_eq!(lox.source(1), Some("var s = \"é\"; f()"));
+    assert_eq!(lox.source(2), None);
+
+    let mut lox = Lox::new();
+
+    lox.run("print 1;")?;
+
+    assert_eq!(lox.source(0), None);
+
+    Ok(())
+  }
+
+  #[test]
+  fn errors() -> Result {
+    let mut lox = Lox::new();
+
+    assert_eq!(
+      lox.run("print ;").unwrap_err().to_string(),
+      "Parser Error: Expect expression. [line 1]"
+    );
+
+    assert_eq!(
+      lox.run("print 1").unwrap_err().to_string(),
+      "Parser Error: Expect ';' after value. [line 1]"
+    );
+
+    assert_eq!(
+      lox.run("-nil;").unwrap_err().to_string(),
+      "Runtime Error: Operand must be a number. [line 1]"
+    );
+
+    assert_eq!(display(lox.run("1;")?), Some("1".into()));
+
+    Ok(())
+  }
+
+  #[test]
+  fn output() -> Result {
+    let output = Buffer::default();
+
+    let mut lox = Lox::new().output(output.clone());
+
+    lox.run("print \"hello\";")?;
+    lox.run("var a = 1; print a + 1;")?;
+
+    assert_eq!(output.contents(), "hello\n2\n");
+
+    Ok(())
+  }
+
+  #[test]
+  fn introspection() -> Result {
+    let mut lox = Lox::new();
+
+    lox.run(
+      "
+      class A { f() {} }
+      class B < A { init() { this.x = 1; } g() {} }
+      var b = B();
+      ",
+    )?;
+
+    let mut globals = lox.globals();
+    globals.sort();
+
+    assert_eq!(globals, vec!["A", "B", "b", "clock"]);
+
+    assert_eq!(
+      lox.global("b").map(|value| value.members()),
+      Some(vec!["f".into(), "g".into(), "init".into(), "x".into()])
+    );
+
+    assert_eq!(lox.global("A").map(|value| value.members()), Some(vec![]));
+    assert_eq!(lox.global("c"), None);
+
+    Ok(())
+  }
+
+  #[test]
+  fn natives() -> Result {
+    let log = Rc::new(RefCell::new(Vec::new()));
+
+    let mut lox = Lox::new();
+
+    lox.define_native("log", 1, {
+      let log = log.clone();
+      move |_, arguments| {
+        log.borrow_mut().push(arguments[0].to_string());
+        Ok(Value::Literal(Literal::Nil))
+      }
+    });
+
+    lox.run("log(\"a\"); log(1 + 1);")?;
+
+    assert_eq!(*log.borrow(), vec!["a", "2"]);
+
+    Ok(())
+  }
+}
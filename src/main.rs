@@ -1,33 +1,49 @@
-use crate::common::*;
+use {
+  crate::{arguments::Arguments, cli_error::CliError, helper::Helper},
+  ansi_term::{Color::Red, Style},
+  lox::Diagnostic,
+  rustyline::error::ReadlineError,
+  std::{fmt::Display, process},
+  structopt::StructOpt,
+};
 
 mod arguments;
-mod common;
-mod error;
-mod expr;
-mod interpreter;
-mod lexer;
-mod literal;
-mod parser;
-mod position;
-mod printer;
-mod token;
-mod token_kind;
-mod visitor;
+mod cli_error;
+mod helper;
 
 fn main() {
   if let Err(error) = Arguments::from_args().run() {
-    if let Error::Readline { ref source } = error {
-      if let ReadlineError::Interrupted | ReadlineError::Eof = source {
-        return;
-      }
+    if let CliError::Readline {
+      source: ReadlineError::Interrupted | ReadlineError::Eof,
+    } = error
+    {
+      return;
     }
 
-    eprintln!(
-      "{}{}",
-      Red.paint("error"),
-      Style::new().bold().paint(format!(": {}", error))
-    );
+    report(&error);
+
+    process::exit(error.code());
+  }
+}
 
-    process::exit(1);
+/// Print `error` to standard error, rendering it against its source when
+/// there is one.
+fn report(error: &CliError) {
+  match error {
+    CliError::Script { path, src, error } => {
+      for error in error.errors() {
+        eprint!("{}", Diagnostic::new(error, src).path(path.as_deref()));
+      }
+    }
+    error => header(error),
   }
 }
+
+/// Print `error` to standard error on its own, as a single line.
+fn header(error: &dyn Display) {
+  eprintln!(
+    "{}{}",
+    Red.paint("error"),
+    Style::new().bold().paint(format!(": {}", error))
+  );
+}
@@ -0,0 +1,48 @@
+use crate::common::*;
+
+/// The signature of a host function callable from Lox.
+pub(crate) type NativeFunction =
+  dyn Fn(&mut Interpreter, &[Value]) -> Result<Value>;
+
+/// A function implemented in Rust and installed with
+/// `Interpreter::define_native`.
+pub struct Native {
+  pub(crate) arity: usize,
+  pub(crate) function: Box<NativeFunction>,
+  pub(crate) name: String,
+}
+
+impl Native {
+  /// The number of arguments this function expects.
+  pub fn arity(&self) -> usize {
+    self.arity
+  }
+
+  /// The global name this function was installed under.
+  pub fn name(&self) -> &str {
+    &self.name
+  }
+
+  pub(crate) fn call(
+    &self,
+    interpreter: &mut Interpreter,
+    arguments: Vec<Value>,
+  ) -> Result<Value> {
+    (self.function)(interpreter, &arguments)
+  }
+}
+
+impl fmt::Debug for Native {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    f.debug_struct("Native")
+      .field("arity", &self.arity)
+      .field("name", &self.name)
+      .finish()
+  }
+}
+
+impl Display for Native {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    write!(f, "<native fn>")
+  }
+}
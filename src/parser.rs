@@ -1,32 +1,107 @@
 use crate::common::*;
 
+/// The maximum number of parameters a function can declare, and arguments a
+/// call can pass.
+const MAX_ARGUMENTS: usize = 255;
+
 #[derive(Debug)]
-pub(crate) struct Parser<'src> {
+pub struct Parser {
+  errors: RefCell<Vec<Error>>,
   next: Cell<usize>,
-  tokens: Vec<Token<'src>>,
+  /// The positions of error tokens, and of the end of the source when an
+  /// error token runs up to it. Syntax errors there are just knock-on effects
+  /// of a lexical error that has already been reported.
+  symptoms: Vec<Position>,
+  tokens: Vec<Token>,
 }
 
-impl<'src> Parser<'src> {
-  /// Parse `tokens`.
-  pub(crate) fn parse(tokens: Vec<Token<'src>>) -> Result<Expr<'src>> {
+impl Parser {
+  /// Parse `tokens`, reporting every syntax error found rather than just the
+  /// first.
+  pub fn parse(tokens: Vec<Token>) -> Result<Vec<Stmt>> {
     Self::new(tokens).parse_ast()
   }
 
-  /// Construct and return a new `Parser` instance based on `tokens`.
-  fn new(tokens: Vec<Token<'src>>) -> Self {
+  /// Construct and return a new `Parser` instance based on `tokens`. The
+  /// errors of any error tokens are reported alongside syntax errors, and the
+  /// tokens themselves are left in the stream to be parsed as placeholders.
+  fn new(tokens: Vec<Token>) -> Self {
+    let errors = tokens.iter().filter_map(Token::error).collect();
+
+    let mut symptoms = Vec::new();
+
+    for (i, token) in tokens.iter().enumerate() {
+      if token.error().is_some() {
+        symptoms.push(token.position.clone());
+
+        if let Some(next) = tokens.get(i + 1).filter(|next| next.kind == Eof) {
+          symptoms.push(next.position.clone());
+        }
+      }
+    }
+
     Self {
+      errors: RefCell::new(errors),
       next: Cell::new(0),
+      symptoms,
       tokens,
     }
   }
 
   /// Parse a lox flat token stream.
-  fn parse_ast(&self) -> Result<Expr<'src>> {
-    self.expression()
+  fn parse_ast(&self) -> Result<Vec<Stmt>> {
+    let statements = self.program();
+
+    let mut errors = self.errors.take();
+
+    if !errors.is_empty() {
+      errors
+        .sort_by_key(|error| error.position().map(|position| position.start));
+
+      return Err(Error::Compile { errors });
+    }
+
+    Ok(statements)
+  }
+
+  /// Record a syntax error without unwinding, for errors that leave the
+  /// parser in a well defined state.
+  fn report(&self, error: Error) {
+    // Parsing stops when the stack runs low, so anything after that is
+    // noise.
+    if self
+      .errors
+      .borrow()
+      .iter()
+      .any(|error| matches!(error, Error::Limit { .. }))
+    {
+      return;
+    }
+
+    if let Error::Parser { position, .. } = &error {
+      if self.symptoms.contains(position) {
+        return;
+      }
+    }
+
+    self.errors.borrow_mut().push(error);
+  }
+
+  /// Fail rather than recurse any deeper if the native stack is about to run
+  /// out, so that deeply nested source can't crash the host.
+  fn guard(&self) -> Result {
+    if Limits::stack_exhausted() {
+      return Err(Error::Limit {
+        kind: LimitKind::Stack,
+        position: Some(self.peek().position),
+      });
+    }
+
+    Ok(())
   }
 
   /// Advance the current position.
-  fn advance(&self) -> Token<'src> {
+  fn advance(&self) -> Token {
     if !self.is_end() {
       self.next.set(self.next.get() + 1);
     }
@@ -35,27 +110,30 @@ impl<'src> Parser<'src> {
 
   /// Advance and return the token at our current position if the current token
   /// kind matches `kind`.
-  fn consume<'a>(
-    &self,
-    kind: TokenKind,
-    message: &'a str,
-  ) -> Result<Token<'src>> {
+  fn consume(&self, kind: TokenKind, message: &str) -> Result<Token> {
     if self.check(kind.clone()) {
       return Ok(self.advance());
     }
 
-    Err(Error::Parser {
+    Err(self.error(&self.peek(), message))
+  }
+
+  /// Construct a syntax error pointing at `token`.
+  fn error(&self, token: &Token, message: &str) -> Error {
+    Error::Parser {
       message: message.to_owned(),
-    })
+      position: token.position.clone(),
+      help: None,
+    }
   }
 
   /// Return the token at located at the previous position.
-  fn prev(&self) -> Token<'src> {
+  fn prev(&self) -> Token {
     self.tokens[self.next.get() - 1].clone()
   }
 
   /// Return the token located at the current position.
-  fn peek(&self) -> Token<'_> {
+  fn peek(&self) -> Token {
     self.tokens[self.next.get()].clone()
   }
 
@@ -96,13 +174,383 @@ impl<'src> Parser<'src> {
     self.peek().kind == Eof
   }
 
+  /// Method for the `program` grammar rule.
+  fn program(&self) -> Vec<Stmt> {
+    let mut statements = Vec::new();
+
+    while !self.is_end() {
+      statements.extend(self.declaration());
+    }
+
+    statements
+  }
+
+  /// Method for the `declaration` grammar rule.
+  ///
+  /// This is where panic mode ends: a syntax error anywhere inside the
+  /// declaration is recorded, and parsing resumes at the start of the next
+  /// statement. Running low on stack instead abandons the rest of the source.
+  fn declaration(&self) -> Option<Stmt> {
+    match self.parse_declaration() {
+      Ok(statement) => Some(statement),
+      Err(error @ Error::Limit { .. }) => {
+        self.report(error);
+        self.next.set(self.tokens.len() - 1);
+        None
+      }
+      Err(error) => {
+        self.report(error);
+        self.sync();
+        None
+      }
+    }
+  }
+
+  fn parse_declaration(&self) -> Result<Stmt> {
+    if self.match_kind(Class) {
+      return self.class_declaration();
+    }
+
+    if self.match_kind(Fun) {
+      return Ok(Stmt::Function {
+        declaration: Rc::new(self.function("function")?),
+      });
+    }
+
+    if self.match_kind(Var) {
+      return self.var_declaration();
+    }
+
+    self.statement()
+  }
+
+  /// Method for the `classDecl` grammar rule.
+  fn class_declaration(&self) -> Result<Stmt> {
+    let name = self.consume(Identifier, "Expect class name.")?;
+
+    let superclass = if self.match_kind(Less) {
+      Some(Expr::Variable {
+        name: self.consume(Identifier, "Expect superclass name.")?,
+        depth: Depth::default(),
+      })
+    } else {
+      None
+    };
+
+    self.consume(BraceL, "Expect '{' before class body.")?;
+
+    let mut methods = Vec::new();
+
+    while !self.check(BraceR) && !self.is_end() {
+      methods.push(Rc::new(self.function("method")?));
+    }
+
+    self.consume(BraceR, "Expect '}' after class body.")?;
+
+    Ok(Stmt::Class {
+      name,
+      superclass,
+      methods,
+    })
+  }
+
+  /// Method for the `function` grammar rule, `kind` names what is being
+  /// declared in error messages.
+  fn function(&self, kind: &str) -> Result<FunctionDeclaration> {
+    let name = self.consume(Identifier, &format!("Expect {} name.", kind))?;
+
+    self.consume(ParenL, &format!("Expect '(' after {} name.", kind))?;
+
+    let mut params = Vec::new();
+
+    if !self.check(ParenR) {
+      loop {
+        if params.len() >= MAX_ARGUMENTS {
+          self.report(self.error(
+            &self.peek(),
+            &format!("Can't have more than {} parameters.", MAX_ARGUMENTS),
+          ));
+        }
+
+        params.push(self.consume(Identifier, "Expect parameter name.")?);
+
+        if !self.match_kind(Comma) {
+          break;
+        }
+      }
+    }
+
+    self.consume(ParenR, "Expect ')' after parameters.")?;
+
+    self.consume(BraceL, &format!("Expect '{{' before {} body.", kind))?;
+
+    Ok(FunctionDeclaration {
+      name,
+      params,
+      body: self.block()?,
+    })
+  }
+
+  /// Method for the `varDecl` grammar rule.
+  fn var_declaration(&self) -> Result<Stmt> {
+    let name = self.consume(Identifier, "Expect variable name.")?;
+
+    let initializer = if self.match_kind(Equal) {
+      Some(self.expression()?)
+    } else {
+      None
+    };
+
+    self.consume(Semicolon, "Expect ';' after variable declaration.")?;
+
+    Ok(Stmt::Var { name, initializer })
+  }
+
+  /// Method for the `statement` grammar rule.
+  fn statement(&self) -> Result<Stmt> {
+    self.guard()?;
+
+    if self.match_kind(For) {
+      return self.for_statement();
+    }
+
+    if self.match_kind(If) {
+      return self.if_statement();
+    }
+
+    if self.match_kind(Print) {
+      return self.print_statement();
+    }
+
+    if self.match_kind(Return) {
+      return self.return_statement();
+    }
+
+    if self.match_kind(While) {
+      return self.while_statement();
+    }
+
+    if self.match_kind(BraceL) {
+      return Ok(Stmt::Block {
+        statements: self.block()?,
+      });
+    }
+
+    self.expression_statement()
+  }
+
+  /// Method for the `block` grammar rule.
+  fn block(&self) -> Result<Vec<Stmt>> {
+    self.guard()?;
+
+    let mut statements = Vec::new();
+
+    while !self.check(BraceR) && !self.is_end() {
+      statements.extend(self.declaration());
+    }
+
+    self.consume(BraceR, "Expect '}' after block.")?;
+
+    Ok(statements)
+  }
+
+  /// Method for the `forStmt` grammar rule.
+  ///
+  /// There is no dedicated `for` node in the syntax tree, instead the loop is
+  /// desugared into an equivalent `while` loop wrapped in blocks.
+  fn for_statement(&self) -> Result<Stmt> {
+    self.consume(ParenL, "Expect '(' after 'for'.")?;
+
+    let initializer = if self.match_kind(Semicolon) {
+      None
+    } else if self.match_kind(Var) {
+      Some(self.var_declaration()?)
+    } else {
+      Some(self.expression_statement()?)
+    };
+
+    let condition = if self.check(Semicolon) {
+      None
+    } else {
+      Some(self.expression()?)
+    };
+
+    self.consume(Semicolon, "Expect ';' after loop condition.")?;
+
+    let increment = if self.check(ParenR) {
+      None
+    } else {
+      Some(self.expression()?)
+    };
+
+    self.consume(ParenR, "Expect ')' after for clauses.")?;
+
+    let mut body = self.statement()?;
+
+    if let Some(increment) = increment {
+      body = Stmt::Block {
+        statements: vec![
+          body,
+          Stmt::Expression {
+            expression: increment,
+          },
+        ],
+      };
+    }
+
+    body = Stmt::While {
+      condition: condition.unwrap_or(Expr::Literal {
+        value: Literal::Boolean(true),
+      }),
+      body: Box::new(body),
+    };
+
+    if let Some(initializer) = initializer {
+      body = Stmt::Block {
+        statements: vec![initializer, body],
+      };
+    }
+
+    Ok(body)
+  }
+
+  /// Method for the `ifStmt` grammar rule.
+  fn if_statement(&self) -> Result<Stmt> {
+    self.consume(ParenL, "Expect '(' after 'if'.")?;
+    let condition = self.expression()?;
+    self.consume(ParenR, "Expect ')' after if condition.")?;
+
+    let then_branch = Box::new(self.statement()?);
+
+    let else_branch = if self.match_kind(Else) {
+      Some(Box::new(self.statement()?))
+    } else {
+      None
+    };
+
+    Ok(Stmt::If {
+      condition,
+      then_branch,
+      else_branch,
+    })
+  }
+
+  /// Method for the `returnStmt` grammar rule.
+  fn return_statement(&self) -> Result<Stmt> {
+    let keyword = self.prev();
+
+    let value = if self.check(Semicolon) {
+      None
+    } else {
+      Some(self.expression()?)
+    };
+
+    self.consume(Semicolon, "Expect ';' after return value.")?;
+
+    Ok(Stmt::Return { keyword, value })
+  }
+
+  /// Method for the `whileStmt` grammar rule.
+  fn while_statement(&self) -> Result<Stmt> {
+    self.consume(ParenL, "Expect '(' after 'while'.")?;
+    let condition = self.expression()?;
+    self.consume(ParenR, "Expect ')' after condition.")?;
+
+    Ok(Stmt::While {
+      condition,
+      body: Box::new(self.statement()?),
+    })
+  }
+
+  /// Method for the `printStmt` grammar rule.
+  fn print_statement(&self) -> Result<Stmt> {
+    let expression = self.expression()?;
+    self.consume(Semicolon, "Expect ';' after value.")?;
+    Ok(Stmt::Print { expression })
+  }
+
+  /// Method for the `exprStmt` grammar rule.
+  fn expression_statement(&self) -> Result<Stmt> {
+    let expression = self.expression()?;
+    self.consume(Semicolon, "Expect ';' after expression.")?;
+    Ok(Stmt::Expression { expression })
+  }
+
   /// Method for the `expression` grammar rule.
-  fn expression(&self) -> Result<Expr<'src>> {
-    self.equality()
+  fn expression(&self) -> Result<Expr> {
+    self.guard()?;
+
+    self.assignment()
+  }
+
+  /// Method for the `assignment` grammar rule.
+  fn assignment(&self) -> Result<Expr> {
+    self.guard()?;
+
+    let expr = self.or()?;
+
+    if self.match_kind(Equal) {
+      let equals = self.prev();
+
+      let value = self.assignment()?;
+
+      return Ok(match expr {
+        Expr::Variable { name, .. } => Expr::Assign {
+          name,
+          value: Box::new(value),
+          depth: Depth::default(),
+        },
+        Expr::Get { object, name } => Expr::Set {
+          object,
+          name,
+          value: Box::new(value),
+        },
+        _ => {
+          self.report(Error::Parser {
+            message: "Invalid assignment target.".into(),
+            position: equals.position,
+            help: Some("Only variables and fields can be assigned to.".into()),
+          });
+          expr
+        }
+      });
+    }
+
+    Ok(expr)
+  }
+
+  /// Method for the `logic_or` grammar rule.
+  fn or(&self) -> Result<Expr> {
+    let mut expr = self.and()?;
+
+    while self.match_kind(Or) {
+      expr = Expr::Logical {
+        left: Box::new(expr),
+        operator: self.prev(),
+        right: Box::new(self.and()?),
+      };
+    }
+
+    Ok(expr)
+  }
+
+  /// Method for the `logic_and` grammar rule.
+  fn and(&self) -> Result<Expr> {
+    let mut expr = self.equality()?;
+
+    while self.match_kind(And) {
+      expr = Expr::Logical {
+        left: Box::new(expr),
+        operator: self.prev(),
+        right: Box::new(self.equality()?),
+      };
+    }
+
+    Ok(expr)
   }
 
   /// Method for the `equality` grammar rule.
-  fn equality(&self) -> Result<Expr<'src>> {
+  fn equality(&self) -> Result<Expr> {
     let mut expr = self.comparison()?;
 
     while self.match_kinds(vec![BangEqual, EqualEqual]) {
@@ -117,7 +565,7 @@ impl<'src> Parser<'src> {
   }
 
   /// Method for the `comparison` grammar rule.
-  fn comparison(&self) -> Result<Expr<'src>> {
+  fn comparison(&self) -> Result<Expr> {
     let mut expr = self.term()?;
 
     while self.match_kinds(vec![Greater, GreaterEqual, Less, LessEqual]) {
@@ -132,7 +580,7 @@ impl<'src> Parser<'src> {
   }
 
   /// Method for the `term` grammar rule.
-  fn term(&self) -> Result<Expr<'src>> {
+  fn term(&self) -> Result<Expr> {
     let mut expr = self.factor()?;
 
     while self.match_kinds(vec![Minus, Plus]) {
@@ -147,7 +595,7 @@ impl<'src> Parser<'src> {
   }
 
   /// Method for the `factor` grammar rule.
-  fn factor(&self) -> Result<Expr<'src>> {
+  fn factor(&self) -> Result<Expr> {
     let mut expr = self.unary()?;
 
     while self.match_kinds(vec![Slash, Star]) {
@@ -162,7 +610,9 @@ impl<'src> Parser<'src> {
   }
 
   /// Method for the `unary` grammar rule.
-  fn unary(&self) -> Result<Expr<'src>> {
+  fn unary(&self) -> Result<Expr> {
+    self.guard()?;
+
     if self.match_kinds(vec![Bang, Minus]) {
       return Ok(Expr::Unary {
         operator: self.prev(),
@@ -170,11 +620,59 @@ impl<'src> Parser<'src> {
       });
     }
 
-    self.primary()
+    self.call()
+  }
+
+  /// Method for the `call` grammar rule.
+  fn call(&self) -> Result<Expr> {
+    let mut expr = self.primary()?;
+
+    loop {
+      if self.match_kind(ParenL) {
+        expr = self.finish_call(expr)?;
+      } else if self.match_kind(Dot) {
+        expr = Expr::Get {
+          object: Box::new(expr),
+          name: self.consume(Identifier, "Expect property name after '.'.")?,
+        };
+      } else {
+        break;
+      }
+    }
+
+    Ok(expr)
+  }
+
+  /// Parse the argument list of a call to `callee`, after the opening `(`.
+  fn finish_call(&self, callee: Expr) -> Result<Expr> {
+    let mut arguments = Vec::new();
+
+    if !self.check(ParenR) {
+      loop {
+        if arguments.len() >= MAX_ARGUMENTS {
+          self.report(self.error(
+            &self.peek(),
+            &format!("Can't have more than {} arguments.", MAX_ARGUMENTS),
+          ));
+        }
+
+        arguments.push(self.expression()?);
+
+        if !self.match_kind(Comma) {
+          break;
+        }
+      }
+    }
+
+    Ok(Expr::Call {
+      callee: Box::new(callee),
+      paren: self.consume(ParenR, "Expect ')' after arguments.")?,
+      arguments,
+    })
   }
 
   /// Method for the `primary` grammar rule.
-  fn primary(&self) -> Result<Expr<'src>> {
+  fn primary(&self) -> Result<Expr> {
     if self.match_kind(False) {
       return Ok(Expr::Literal {
         value: Literal::Boolean(false),
@@ -188,50 +686,107 @@ impl<'src> Parser<'src> {
     }
 
     if self.match_kind(Number) {
+      let token = self.prev();
+
+      let lexeme = token.lexeme().ok_or_else(|| {
+        self.error(&token, "Expected valid lexeme on `Number` token")
+      })?;
+
       return Ok(Expr::Literal {
-        value: Literal::Number(
-          self
-            .prev()
-            .lexeme
-            .ok_or(Error::Parser {
-              message: "Expected valid lexeme on `Number` token".to_owned(),
-            })?
-            .parse()?,
-        ),
+        value: Literal::Number(Lexer::number(lexeme)?),
       });
     }
 
     if self.match_kind(StringLiteral) {
-      return Ok(Expr::Literal {
-        value: Literal::String(
-          self
-            .prev()
-            .lexeme
-            .ok_or(Error::Parser {
-              message: "Expected valid lexeme on `StringLiteral` token"
-                .to_owned(),
-            })?
-            .to_string(),
-        ),
-      });
+      return self.string_segment();
+    }
+
+    if self.match_kind(Interpolation) {
+      return self.interpolation();
     }
 
     if self.match_kind(True) {
       return Ok(Expr::Literal {
-        value: Literal::Boolean(false),
+        value: Literal::Boolean(true),
+      });
+    }
+
+    if self.match_kind(Super) {
+      let keyword = self.prev();
+      self.consume(Dot, "Expect '.' after 'super'.")?;
+      return Ok(Expr::Super {
+        keyword,
+        method: self.consume(Identifier, "Expect superclass method name.")?,
+        depth: Depth::default(),
+      });
+    }
+
+    if self.match_kind(This) {
+      return Ok(Expr::This {
+        keyword: self.prev(),
+        depth: Depth::default(),
+      });
+    }
+
+    if self.match_kind(Identifier) {
+      return Ok(Expr::Variable {
+        name: self.prev(),
+        depth: Depth::default(),
       });
     }
 
     if self.match_kind(ParenL) {
       let expr = self.expression()?;
-      self.consume(ParenR, "Expected closing )")?;
+      self.consume(ParenR, "Expect ')' after expression.")?;
       return Ok(Expr::Grouping {
         expression: Box::new(expr),
       });
     }
 
-    Err(Error::Parser {
-      message: "Invalid expression".into(),
+    // The lexical error has already been reported, so stand in a `nil` and
+    // carry on parsing the rest of the expression.
+    if self.peek().error().is_some() {
+      self.advance();
+      return Ok(Expr::Literal {
+        value: Literal::Nil,
+      });
+    }
+
+    Err(self.error(&self.peek(), "Expect expression."))
+  }
+
+  /// Parse an interpolated string, after its opening `Interpolation` segment.
+  fn interpolation(&self) -> Result<Expr> {
+    let mut parts = vec![self.string_segment()?];
+
+    loop {
+      parts.push(self.expression()?);
+
+      if !self.match_kind(Interpolation) {
+        break;
+      }
+
+      parts.push(self.string_segment()?);
+    }
+
+    self.consume(StringLiteral, "Expect '}' after interpolated expression.")?;
+
+    parts.push(self.string_segment()?);
+
+    Ok(Expr::Interpolation { parts })
+  }
+
+  /// Turn the string or interpolation segment we just consumed into a string
+  /// literal, decoding its escape sequences.
+  fn string_segment(&self) -> Result<Expr> {
+    let token = self.prev();
+
+    let lexeme = token.lexeme().ok_or_else(|| {
+      self.error(&token, "Expected valid lexeme on string token")
+    })?;
+
+    Ok(Expr::Literal {
+      value: Literal::String(Lexer::unescape(lexeme)),
     })
   }
 
@@ -255,3 +810,202 @@ impl<'src> Parser<'src> {
     }
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use {super::*, pretty_assertions::assert_eq, std::thread};
+
+  struct Test {
+    source: String,
+    errors: Vec<String>,
+  }
+
+  impl Test {
+    fn new() -> Self {
+      Self {
+        source: String::new(),
+        errors: Vec::new(),
+      }
+    }
+
+    fn source(self, source: &str) -> Self {
+      Self {
+        source: source.to_owned(),
+        ..self
+      }
+    }
+
+    fn errors(self, errors: Vec<&str>) -> Self {
+      Self {
+        errors: errors.iter().map(|s| s.to_string()).collect(),
+        ..self
+      }
+    }
+
+    fn run(&self) -> Result {
+      let errors = match Parser::parse(Lexer::tokens(&self.source)) {
+        Ok(_) => Vec::new(),
+        Err(Error::Compile { errors }) => errors,
+        Err(error) => return Err(error),
+      };
+
+      assert_eq!(
+        errors
+          .iter()
+          .map(ToString::to_string)
+          .collect::<Vec<String>>(),
+        self.errors
+      );
+
+      Ok(())
+    }
+  }
+
+  #[test]
+  fn valid() -> Result {
+    Test::new()
+      .source(
+        "
+        class A < B { init(a) { this.a = a; } }
+        fun f(a, b) { return a(b).c; }
+        for (var i = 0; i < 10; i = i + 1) if (i) print i; else {}
+        ",
+      )
+      .run()
+  }
+
+  #[test]
+  fn multiple_errors() -> Result {
+    Test::new()
+      .source(
+        "
+        var = 1;
+        print 1 +;
+        var ok = 2;
+        fun (a) {}
+        print ok
+        ",
+      )
+      .errors(vec![
+        "Parser Error: Expect variable name. [line 2]",
+        "Parser Error: Expect expression. [line 3]",
+        "Parser Error: Expect function name. [line 5]",
+        "Parser Error: Expect ';' after value. [line 7]",
+      ])
+      .run()
+  }
+
+  #[test]
+  fn errors_inside_blocks() -> Result {
+    Test::new()
+      .source(
+        "
+        fun f() {
+          var a = ;
+          return 1
+        }
+        print 2;
+        ",
+      )
+      .errors(vec![
+        "Parser Error: Expect expression. [line 3]",
+        "Parser Error: Expect ';' after return value. [line 5]",
+        "Parser Error: Expect '}' after block. [line 7]",
+      ])
+      .run()
+  }
+
+  #[test]
+  fn invalid_assignment_target() -> Result {
+    Test::new()
+      .source("1 = 2;\na + b = c;")
+      .errors(vec![
+        "Parser Error: Invalid assignment target. [line 1]",
+        "Parser Error: Invalid assignment target. [line 2]",
+      ])
+      .run()
+  }
+
+  #[test]
+  fn lexical_errors() -> Result {
+    Test::new()
+      .source(
+        "
+        var a = 1 @ 2;
+        print 1 +;
+        print \"unterminated;
+        ",
+      )
+      .errors(vec![
+        "Lexer Error: Unexpected character. [line 2]",
+        "Parser Error: Expect expression. [line 3]",
+        "Lexer Error: Unterminated string. [line 4]",
+      ])
+      .run()
+  }
+
+  #[test]
+  fn lexical_errors_in_expressions() -> Result {
+    for (source, error) in [
+      (
+        "print 0b;",
+        "Lexer Error: Expect binary digits after '0b'. [line 1]",
+      ),
+      (
+        "print 12abc;",
+        "Lexer Error: Invalid digit in number literal. [line 1]",
+      ),
+      (
+        "print \"\\u{D800}\";",
+        "Lexer Error: Invalid escape sequence. [line 1]",
+      ),
+      (
+        "print \"${\";",
+        "Lexer Error: Unterminated string. [line 1]",
+      ),
+      (
+        "print -@ + 1;",
+        "Lexer Error: Unexpected character. [line 1]",
+      ),
+    ] {
+      Test::new().source(source).errors(vec![error]).run()?;
+    }
+
+    Ok(())
+  }
+
+  #[test]
+  fn unterminated_interpolation() -> Result {
+    Test::new()
+      .source("print \"a ${b c}\";")
+      .errors(vec![
+        "Parser Error: Expect '}' after interpolated expression. [line 1]",
+      ])
+      .run()
+  }
+
+  #[test]
+  fn deep_nesting() {
+    let errors = thread::Builder::new()
+      .stack_size(256 * 1024)
+      .spawn(|| {
+        [
+          format!("print {}1{};", "(".repeat(100_000), ")".repeat(100_000)),
+          format!("print {}1;", "-".repeat(100_000)),
+          format!("{}{}", "{".repeat(100_000), "}".repeat(100_000)),
+        ]
+        .iter()
+        .map(|source| {
+          Parser::parse(Lexer::tokens(source))
+            .unwrap_err()
+            .to_string()
+        })
+        .collect::<Vec<String>>()
+      })
+      .unwrap()
+      .join()
+      .unwrap();
+
+    assert_eq!(errors, vec!["Runtime Error: Stack overflow. [line 1]"; 3]);
+  }
+}
@@ -1,6 +1,17 @@
+/// The location of a token in the source, as byte offsets for slicing along
+/// with the human readable line and column it starts at.
 #[derive(Debug, Default, Clone, PartialEq)]
-pub(crate) struct Position {
-  pub(crate) current: usize,
-  pub(crate) line: usize,
-  pub(crate) start: usize,
+pub struct Position {
+  /// The column, counted in characters from 1, the token starts at.
+  pub column: usize,
+  /// The byte offset one past the end of the token.
+  pub current: usize,
+  /// The line, counted from 1, the token starts on.
+  pub line: usize,
+  /// Which of the sources run in a `Lox` session the token comes from, for
+  /// looking it up with `Lox::source`. Zero unless the session keeps its
+  /// sources.
+  pub source: usize,
+  /// The byte offset of the start of the token.
+  pub start: usize,
 }
@@ -0,0 +1,491 @@
+use crate::common::*;
+
+#[derive(Debug)]
+pub struct Resolver {
+  class: ClassKind,
+  errors: Vec<Error>,
+  /// Whether the native stack ran low, after which nothing more is resolved.
+  exhausted: bool,
+  function: FunctionKind,
+  scopes: Vec<HashMap<String, bool>>,
+}
+
+impl Visitor<()> for Resolver {
+  fn visit_expr(&mut self, expr: &Expr) {
+    match expr {
+      Expr::Assign { name, value, depth } => {
+        self.resolve_expr(value);
+        self.resolve_local(name, depth);
+      }
+      Expr::Binary { left, right, .. } | Expr::Logical { left, right, .. } => {
+        self.resolve_expr(left);
+        self.resolve_expr(right);
+      }
+      Expr::Call {
+        callee, arguments, ..
+      } => {
+        self.resolve_expr(callee);
+        arguments
+          .iter()
+          .for_each(|argument| self.resolve_expr(argument));
+      }
+      Expr::Get { object, .. } => self.resolve_expr(object),
+      Expr::Grouping { expression } => self.resolve_expr(expression),
+      Expr::Interpolation { parts } => {
+        parts.iter().for_each(|part| self.resolve_expr(part));
+      }
+      Expr::Literal { .. } => {}
+      Expr::Set { object, value, .. } => {
+        self.resolve_expr(value);
+        self.resolve_expr(object);
+      }
+      Expr::Super { keyword, depth, .. } => match self.class {
+        ClassKind::None => {
+          self.error(keyword, "Can't use 'super' outside of a class.")
+        }
+        ClassKind::Class => self
+          .error(keyword, "Can't use 'super' in a class with no superclass."),
+        ClassKind::Subclass => self.resolve_local(keyword, depth),
+      },
+      Expr::This { keyword, depth } => {
+        if self.class == ClassKind::None {
+          self.error(keyword, "Can't use 'this' outside of a class.");
+          return;
+        }
+
+        self.resolve_local(keyword, depth);
+      }
+      Expr::Unary { right, .. } => self.resolve_expr(right),
+      Expr::Variable { name, depth } => {
+        let key = name.lexeme().unwrap_or_default();
+
+        if let Some(false) =
+          self.scopes.last().and_then(|scope| scope.get(key).copied())
+        {
+          self.error(name, "Can't read local variable in its own initializer.");
+        }
+
+        self.resolve_local(name, depth);
+      }
+    }
+  }
+}
+
+impl Resolver {
+  /// Resolve every variable reference in `statements` to the number of scopes
+  /// between it and its declaration, reporting every error found rather than
+  /// just the first.
+  pub fn resolve(statements: &[Stmt]) -> Result {
+    let mut resolver = Self::new();
+
+    resolver.resolve_stmts(statements);
+
+    if !resolver.errors.is_empty() {
+      return Err(Error::Compile {
+        errors: resolver.errors,
+      });
+    }
+
+    Ok(())
+  }
+
+  /// Construct and return a new `Resolver` positioned at the top level.
+  fn new() -> Self {
+    Self {
+      class: ClassKind::None,
+      errors: Vec::new(),
+      exhausted: false,
+      function: FunctionKind::None,
+      scopes: Vec::new(),
+    }
+  }
+
+  /// Record an error at `token` and carry on resolving.
+  fn error(&mut self, token: &Token, message: &str) {
+    self.errors.push(Error::Resolver {
+      message: message.to_owned(),
+      position: token.position.clone(),
+    });
+  }
+
+  /// Whether to stop rather than recurse any deeper, recording an error at
+  /// `position` the first time the native stack is about to run out.
+  fn exhausted(&mut self, position: impl FnOnce() -> Option<Position>) -> bool {
+    if !self.exhausted && Limits::stack_exhausted() {
+      self.exhausted = true;
+      self.errors.push(Error::Limit {
+        kind: LimitKind::Stack,
+        position: position(),
+      });
+    }
+
+    self.exhausted
+  }
+
+  fn resolve_stmts(&mut self, statements: &[Stmt]) {
+    statements
+      .iter()
+      .for_each(|statement| self.resolve_stmt(statement));
+  }
+
+  fn resolve_stmt(&mut self, stmt: &Stmt) {
+    if self.exhausted(|| None) {
+      return;
+    }
+
+    match stmt {
+      Stmt::Block { statements } => {
+        self.begin_scope();
+        self.resolve_stmts(statements);
+        self.end_scope();
+      }
+      Stmt::Class {
+        name,
+        superclass,
+        methods,
+      } => self.resolve_class(name, superclass.as_ref(), methods),
+      Stmt::Expression { expression } | Stmt::Print { expression } => {
+        self.resolve_expr(expression)
+      }
+      Stmt::Function { declaration } => {
+        self.declare(&declaration.name);
+        self.define(&declaration.name);
+        self.resolve_function(declaration, FunctionKind::Function);
+      }
+      Stmt::If {
+        condition,
+        then_branch,
+        else_branch,
+      } => {
+        self.resolve_expr(condition);
+        self.resolve_stmt(then_branch);
+
+        if let Some(else_branch) = else_branch {
+          self.resolve_stmt(else_branch);
+        }
+      }
+      Stmt::Return { keyword, value } => {
+        if self.function == FunctionKind::None {
+          self.error(keyword, "Can't return from top-level code.");
+        }
+
+        if let Some(value) = value {
+          if self.function == FunctionKind::Initializer {
+            self.error(keyword, "Can't return a value from an initializer.");
+          }
+
+          self.resolve_expr(value);
+        }
+      }
+      Stmt::Var { name, initializer } => {
+        self.declare(name);
+
+        if let Some(initializer) = initializer {
+          self.resolve_expr(initializer);
+        }
+
+        self.define(name);
+      }
+      Stmt::While { condition, body } => {
+        self.resolve_expr(condition);
+        self.resolve_stmt(body);
+      }
+    }
+  }
+
+  fn resolve_expr(&mut self, expr: &Expr) {
+    if self.exhausted(|| expr.position().cloned()) {
+      return;
+    }
+
+    expr.accept(self)
+  }
+
+  fn resolve_class(
+    &mut self,
+    name: &Token,
+    superclass: Option<&Expr>,
+    methods: &[Rc<FunctionDeclaration>],
+  ) {
+    let enclosing = self.class;
+    self.class = ClassKind::Class;
+
+    self.declare(name);
+    self.define(name);
+
+    if let Some(superclass) = superclass {
+      if let Expr::Variable {
+        name: superclass_name,
+        ..
+      } = superclass
+      {
+        if superclass_name.lexeme == name.lexeme {
+          self.error(superclass_name, "A class can't inherit from itself.");
+        }
+      }
+
+      self.class = ClassKind::Subclass;
+      self.resolve_expr(superclass);
+
+      self.begin_scope();
+      self.scope_insert("super");
+    }
+
+    self.begin_scope();
+    self.scope_insert("this");
+
+    for method in methods {
+      let kind = match method.name.lexeme() {
+        Some("init") => FunctionKind::Initializer,
+        _ => FunctionKind::Method,
+      };
+
+      self.resolve_function(method, kind);
+    }
+
+    self.end_scope();
+
+    if superclass.is_some() {
+      self.end_scope();
+    }
+
+    self.class = enclosing;
+  }
+
+  fn resolve_function(
+    &mut self,
+    declaration: &FunctionDeclaration,
+    kind: FunctionKind,
+  ) {
+    let enclosing = self.function;
+    self.function = kind;
+
+    self.begin_scope();
+
+    for param in &declaration.params {
+      self.declare(param);
+      self.define(param);
+    }
+
+    self.resolve_stmts(&declaration.body);
+
+    self.end_scope();
+
+    self.function = enclosing;
+  }
+
+  /// Record how many scopes out from the innermost one `name` was declared,
+  /// leaving `depth` empty for globals.
+  fn resolve_local(&self, name: &Token, depth: &Depth) {
+    let key = name.lexeme().unwrap_or_default();
+
+    depth.set(
+      self
+        .scopes
+        .iter()
+        .rev()
+        .position(|scope| scope.contains_key(key)),
+    );
+  }
+
+  fn begin_scope(&mut self) {
+    self.scopes.push(HashMap::new());
+  }
+
+  fn end_scope(&mut self) {
+    self.scopes.pop();
+  }
+
+  /// Add `name` to the innermost scope, marked as not yet ready for use.
+  fn declare(&mut self, name: &Token) {
+    let key = name.lexeme().unwrap_or_default();
+
+    let redeclared = match self.scopes.last_mut() {
+      Some(scope) => scope.insert(key.to_owned(), false).is_some(),
+      None => false,
+    };
+
+    if redeclared {
+      self.error(name, "Already a variable with this name in this scope.");
+    }
+  }
+
+  /// Mark `name` as fully initialized and available for use.
+  fn define(&mut self, name: &Token) {
+    self.scope_insert(name.lexeme().unwrap_or_default());
+  }
+
+  fn scope_insert(&mut self, name: &str) {
+    if let Some(scope) = self.scopes.last_mut() {
+      scope.insert(name.to_owned(), true);
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use {super::*, pretty_assertions::assert_eq, std::thread};
+
+  struct Test {
+    source: String,
+    expected: String,
+  }
+
+  impl Test {
+    fn new() -> Self {
+      Self {
+        source: String::new(),
+        expected: String::new(),
+      }
+    }
+
+    fn source(self, source: &str) -> Self {
+      Self {
+        source: source.to_owned(),
+        ..self
+      }
+    }
+
+    fn expected(self, expected: &str) -> Self {
+      Self {
+        expected: expected.to_owned(),
+        ..self
+      }
+    }
+
+    fn run(&self) -> Result {
+      let statements = Parser::parse(Lexer::lex(&self.source)?)?;
+
+      assert_eq!(
+        Resolver::resolve(&statements).unwrap_err().to_string(),
+        self.expected
+      );
+
+      Ok(())
+    }
+  }
+
+  #[test]
+  fn own_initializer() -> Result {
+    Test::new()
+      .source("var a = 1;\n{ var a = a; }")
+      .expected(
+        "Resolver Error: Can't read local variable in its own initializer. \
+         [line 2]",
+      )
+      .run()
+  }
+
+  #[test]
+  fn redeclaration() -> Result {
+    Test::new()
+      .source("fun f() {\n  var a;\n  var a;\n}")
+      .expected(
+        "Resolver Error: Already a variable with this name in this scope. \
+         [line 3]",
+      )
+      .run()
+  }
+
+  #[test]
+  fn top_level_return() -> Result {
+    Test::new()
+      .source("return 1;")
+      .expected("Resolver Error: Can't return from top-level code. [line 1]")
+      .run()
+  }
+
+  #[test]
+  fn initializer_return() -> Result {
+    Test::new()
+      .source("class A {\n  init() { return 1; }\n}")
+      .expected(
+        "Resolver Error: Can't return a value from an initializer. [line 2]",
+      )
+      .run()
+  }
+
+  #[test]
+  fn this_outside_class() -> Result {
+    Test::new()
+      .source("fun f() { return this; }")
+      .expected("Resolver Error: Can't use 'this' outside of a class. [line 1]")
+      .run()
+  }
+
+  #[test]
+  fn super_outside_class() -> Result {
+    Test::new()
+      .source("super.foo();")
+      .expected(
+        "Resolver Error: Can't use 'super' outside of a class. [line 1]",
+      )
+      .run()
+  }
+
+  #[test]
+  fn super_without_superclass() -> Result {
+    Test::new()
+      .source("class A {\n  f() { super.f(); }\n}")
+      .expected(
+        "Resolver Error: Can't use 'super' in a class with no superclass. \
+         [line 2]",
+      )
+      .run()
+  }
+
+  #[test]
+  fn multiple_errors() -> Result {
+    Test::new()
+      .source(
+        "return 1;\nfun f() {\n  var a;\n  var a;\n}\nprint this;\nreturn;",
+      )
+      .expected(
+        "Resolver Error: Can't return from top-level code. [line 1]
+Resolver Error: Already a variable with this name in this scope. [line 4]
+Resolver Error: Can't use 'this' outside of a class. [line 6]
+Resolver Error: Can't return from top-level code. [line 7]",
+      )
+      .run()
+  }
+
+  #[test]
+  fn inherit_from_self() -> Result {
+    Test::new()
+      .source("class A < A {}")
+      .expected("Resolver Error: A class can't inherit from itself. [line 1]")
+      .run()
+  }
+
+  #[test]
+  fn deep_nesting() {
+    let error = thread::Builder::new()
+      .stack_size(256 * 1024)
+      .spawn(|| {
+        let mut expression = Expr::Literal {
+          value: Literal::Nil,
+        };
+
+        for _ in 0..100_000 {
+          expression = Expr::Grouping {
+            expression: Box::new(expression),
+          };
+        }
+
+        let statements = vec![Stmt::Print { expression }];
+
+        let error = Resolver::resolve(&statements).unwrap_err().to_string();
+
+        // Dropping the tree recurses once per level, which this thread
+        // doesn't have the stack for either.
+        mem::forget(statements);
+
+        error
+      })
+      .unwrap()
+      .join()
+      .unwrap();
+
+    assert_eq!(error, "Runtime Error: Stack overflow.");
+  }
+}
@@ -0,0 +1,39 @@
+use crate::common::*;
+
+#[derive(Debug, Clone)]
+pub enum Stmt {
+  Block {
+    statements: Vec<Stmt>,
+  },
+  Class {
+    name: Token,
+    superclass: Option<Expr>,
+    methods: Vec<Rc<FunctionDeclaration>>,
+  },
+  Expression {
+    expression: Expr,
+  },
+  Function {
+    declaration: Rc<FunctionDeclaration>,
+  },
+  If {
+    condition: Expr,
+    then_branch: Box<Stmt>,
+    else_branch: Option<Box<Stmt>>,
+  },
+  Print {
+    expression: Expr,
+  },
+  Return {
+    keyword: Token,
+    value: Option<Expr>,
+  },
+  Var {
+    name: Token,
+    initializer: Option<Expr>,
+  },
+  While {
+    condition: Expr,
+    body: Box<Stmt>,
+  },
+}
@@ -0,0 +1,26 @@
+use crate::common::*;
+
+/// An output sink for tests that can be read back after handing a clone of it
+/// to an interpreter.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Buffer {
+  bytes: Rc<RefCell<Vec<u8>>>,
+}
+
+impl Buffer {
+  /// Everything written so far.
+  pub(crate) fn contents(&self) -> String {
+    String::from_utf8(self.bytes.borrow().clone()).unwrap()
+  }
+}
+
+impl Write for Buffer {
+  fn write(&mut self, bytes: &[u8]) -> io::Result<usize> {
+    self.bytes.borrow_mut().extend_from_slice(bytes);
+    Ok(bytes.len())
+  }
+
+  fn flush(&mut self) -> io::Result<()> {
+    Ok(())
+  }
+}
@@ -1,8 +1,38 @@
 use crate::common::*;
 
 #[derive(Debug, PartialEq, Clone)]
-pub(crate) struct Token<'src> {
+pub struct Token {
   pub(crate) kind: TokenKind,
-  pub(crate) lexeme: Option<&'src str>,
+  pub(crate) lexeme: Option<Rc<str>>,
   pub(crate) position: Position,
 }
+
+impl Token {
+  /// What kind of token this is.
+  pub fn kind(&self) -> &TokenKind {
+    &self.kind
+  }
+
+  /// The source text of this token. String literals and segments leave out
+  /// their delimiters, and the end of file token has none.
+  pub fn lexeme(&self) -> Option<&str> {
+    self.lexeme.as_deref()
+  }
+
+  /// Where this token is in the source.
+  pub fn position(&self) -> &Position {
+    &self.position
+  }
+
+  /// The lexical error this token stands for, if it is an error token.
+  pub(crate) fn error(&self) -> Option<Error> {
+    match &self.kind {
+      TokenKind::Error { message, help } => Some(Error::Lexer {
+        message: message.to_owned(),
+        position: self.position.clone(),
+        help: help.to_owned(),
+      }),
+      _ => None,
+    }
+  }
+}
@@ -1,7 +1,7 @@
 use crate::common::*;
 
 #[derive(Debug, Clone, PartialEq)]
-pub(crate) enum TokenKind {
+pub enum TokenKind {
   And,
   Bang,
   BangEqual,
@@ -14,6 +14,12 @@ pub(crate) enum TokenKind {
   Eof,
   Equal,
   EqualEqual,
+  /// A span of source the lexer couldn't make sense of, kept in the token
+  /// stream so lexing can carry on past it.
+  Error {
+    message: String,
+    help: Option<String>,
+  },
   False,
   For,
   Fun,
@@ -21,6 +27,9 @@ pub(crate) enum TokenKind {
   GreaterEqual,
   Identifier,
   If,
+  /// A string segment ending in `${`, followed by the tokens of the
+  /// interpolated expression.
+  Interpolation,
   Less,
   LessEqual,
   Minus,
@@ -61,6 +70,7 @@ impl Display for TokenKind {
         Eof => "eof",
         Equal => "=",
         EqualEqual => "==",
+        TokenKind::Error { .. } => "error",
         False => "false",
         For => "for",
         Fun => "fun",
@@ -68,6 +78,7 @@ impl Display for TokenKind {
         GreaterEqual => ">=",
         Identifier => "ident",
         If => "if",
+        Interpolation => "interpolation",
         Less => "<",
         LessEqual => "<=",
         Minus => "-",
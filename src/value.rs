@@ -0,0 +1,54 @@
+use crate::common::*;
+
+#[derive(Debug, Clone)]
+pub enum Value {
+  Callable(Callable),
+  Instance(Rc<Instance>),
+  Literal(Literal),
+}
+
+impl Value {
+  /// Lox treats `nil` and `false` as falsey and every other value as truthy.
+  pub fn is_truthy(&self) -> bool {
+    match self {
+      Value::Literal(literal) => literal.is_truthy(),
+      Value::Callable(_) | Value::Instance(_) => true,
+    }
+  }
+
+  /// The names of the properties that can be accessed on this value, which
+  /// only instances have.
+  pub fn members(&self) -> Vec<String> {
+    match self {
+      Value::Instance(instance) => instance.members(),
+      Value::Callable(_) | Value::Literal(_) => Vec::new(),
+    }
+  }
+}
+
+impl PartialEq for Value {
+  fn eq(&self, other: &Self) -> bool {
+    match (self, other) {
+      (Value::Callable(a), Value::Callable(b)) => a == b,
+      (Value::Instance(a), Value::Instance(b)) => Rc::ptr_eq(a, b),
+      (Value::Literal(a), Value::Literal(b)) => a == b,
+      _ => false,
+    }
+  }
+}
+
+impl From<Literal> for Value {
+  fn from(literal: Literal) -> Self {
+    Value::Literal(literal)
+  }
+}
+
+impl Display for Value {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    match self {
+      Value::Callable(callable) => write!(f, "{}", callable),
+      Value::Instance(instance) => write!(f, "{}", instance),
+      Value::Literal(literal) => write!(f, "{}", literal),
+    }
+  }
+}
@@ -1,5 +1,5 @@
 use crate::common::*;
 
 pub(crate) trait Visitor<T> {
-  fn visit_expr(&self, expr: Expr) -> T;
+  fn visit_expr(&mut self, expr: &Expr) -> T;
 }
@@ -0,0 +1,332 @@
+//! Runs every `.lox` file under `tests/lox` and `tests/local` through the
+//! `lox` binary and checks its behaviour against the annotations in the file,
+//! in the format used by the Crafting Interpreters test suite:
+//!
+//! - `// expect: output` expects `output` as the next line printed.
+//! - `// expect runtime error: message` expects the script to fail at runtime
+//!   on that line, exiting with code 70.
+//! - `// Error at 'token': message`, `// Error at end: message`,
+//!   `// Error: message` and `// [line N] Error ...: message` expect a compile
+//!   error on that line, or on line `N`, exiting with code 65. Annotations for
+//!   the reference C implementation, `// [c line N] ...`, are ignored, and
+//!   those for the Java one, `// [java line N] ...`, are used.
+//!
+//! Our diagnostics underline the token an error is at rather than naming it,
+//! so the underlined text is compared with the token an annotation names.
+//!
+//! `tests/lox` holds the reference suite's `test` directory, unchanged, as
+//! copied by `just vendor-tests`. Reference scripts this implementation
+//! intentionally disagrees with are listed in `SKIP`. Scripts of our own live
+//! in `tests/local`.
+
+use std::{
+  fs,
+  path::{Path, PathBuf},
+  process::Command,
+};
+
+/// Reference scripts, or directories of them, that aren't run, and why.
+const SKIP: &[(&str, &str)] = &[
+  (
+    "benchmark",
+    "performance measurements rather than conformance tests",
+  ),
+  (
+    "expressions",
+    "written for the expression-only interpreter of an earlier chapter",
+  ),
+  (
+    "limit/loop_too_large.lox",
+    "clox only: the jump offset limit of its bytecode",
+  ),
+  (
+    "limit/no_reuse_constants.lox",
+    "clox only: the constant table limit of its bytecode",
+  ),
+  (
+    "limit/too_many_constants.lox",
+    "clox only: the constant table limit of its bytecode",
+  ),
+  (
+    "limit/too_many_locals.lox",
+    "clox only: the local slot limit of its bytecode",
+  ),
+  (
+    "limit/too_many_upvalues.lox",
+    "clox only: the upvalue limit of its bytecode",
+  ),
+  (
+    "scanning",
+    "written for the token-printing scanner of an earlier chapter",
+  ),
+  (
+    "unexpected_character.lox",
+    "lexical errors don't cause follow-on syntax errors",
+  ),
+];
+
+#[derive(Debug, Default, PartialEq)]
+struct Outcome {
+  code: Option<i32>,
+  errors: Vec<Diagnosis>,
+  stdout: Vec<String>,
+}
+
+#[derive(Debug, PartialEq)]
+struct Diagnosis {
+  line: usize,
+  /// Where on the line the error is: `'token'`, or `end` for the end of the
+  /// file. Errors the reference reports without a location have none.
+  location: Option<String>,
+  message: String,
+}
+
+impl Outcome {
+  /// The outcome `src` expects according to its annotations.
+  fn expected(src: &str) -> Self {
+    let mut outcome = Self {
+      code: Some(0),
+      ..Self::default()
+    };
+
+    for (i, line) in src.lines().enumerate() {
+      let line_number = i + 1;
+
+      let comment = match line.split_once("// ") {
+        Some((_, comment)) => comment,
+        None => continue,
+      };
+
+      if let Some(output) = comment.strip_prefix("expect: ") {
+        outcome.stdout.push(output.to_owned());
+      } else if let Some(message) =
+        comment.strip_prefix("expect runtime error: ")
+      {
+        outcome.errors.push(Diagnosis {
+          line: line_number,
+          location: None,
+          message: message.to_owned(),
+        });
+        outcome.code = Some(70);
+      } else if let Some(diagnosis) = Self::compile_error(line_number, comment)
+      {
+        outcome.errors.push(diagnosis);
+        outcome.code = Some(65);
+      }
+    }
+
+    outcome
+  }
+
+  /// Parse a compile error annotation, which may name the line it's for.
+  fn compile_error(line_number: usize, comment: &str) -> Option<Diagnosis> {
+    let (line_number, comment) = match comment
+      .strip_prefix("[line ")
+      .or_else(|| comment.strip_prefix("[java line "))
+    {
+      Some(rest) => {
+        let (number, rest) = rest.split_once("] ")?;
+        (number.parse().ok()?, rest)
+      }
+      None => (line_number, comment),
+    };
+
+    let (error, message) = comment.split_once(": ")?;
+
+    let location = match error.strip_prefix("Error") {
+      Some("") => None,
+      Some(location) => Some(location.strip_prefix(" at ")?.to_owned()),
+      None => return None,
+    };
+
+    Some(Diagnosis {
+      line: line_number,
+      location,
+      message: message.to_owned(),
+    })
+  }
+
+  /// The outcome of actually running the script at `path`.
+  fn actual(path: &Path) -> Self {
+    let output = Command::new(env!("CARGO_BIN_EXE_lox"))
+      .arg(path)
+      .output()
+      .unwrap();
+
+    let stderr = strip_ansi(&String::from_utf8_lossy(&output.stderr));
+
+    let mut errors = Vec::new();
+
+    let mut lines = stderr.lines();
+
+    while let Some(line) = lines.next() {
+      let message = match line.strip_prefix("error: ") {
+        Some(message) => message,
+        None => continue,
+      };
+
+      let mut location = lines.next().unwrap_or_default().rsplit(':');
+
+      let column = location.next().and_then(|column| column.parse().ok());
+
+      let line_number = location
+        .next()
+        .and_then(|line_number| line_number.parse().ok())
+        .unwrap_or_default();
+
+      // Skip the gutter, then read the source line and its underline.
+      lines.next();
+
+      let snippet = lines
+        .next()
+        .and_then(|snippet| snippet.split_once(" | "))
+        .map(|(_, snippet)| snippet);
+
+      let underline = lines.next().unwrap_or_default();
+
+      errors.push(Diagnosis {
+        line: line_number,
+        location: snippet
+          .zip(column)
+          .map(|(snippet, column)| Self::location(snippet, column, underline)),
+        message: message.to_owned(),
+      });
+    }
+
+    Self {
+      code: output.status.code(),
+      errors,
+      stdout: String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::to_owned)
+        .collect(),
+    }
+  }
+
+  /// The text of the token underlined in a diagnostic starting at `column`
+  /// of `snippet`, as the reference implementation would name it.
+  fn location(snippet: &str, column: usize, underline: &str) -> String {
+    let width = underline.matches('^').count();
+
+    let token = snippet
+      .chars()
+      .skip(column - 1)
+      .take(width)
+      .collect::<String>();
+
+    if token.is_empty() {
+      "end".to_owned()
+    } else {
+      format!("'{}'", token)
+    }
+  }
+
+  /// Whether this outcome is the one `expected`, ignoring the locations of
+  /// errors the reference reports without one.
+  fn matches(&self, expected: &Outcome) -> bool {
+    self.code == expected.code
+      && self.stdout == expected.stdout
+      && self.errors.len() == expected.errors.len()
+      && self
+        .errors
+        .iter()
+        .zip(&expected.errors)
+        .all(|(actual, expected)| {
+          actual.line == expected.line
+            && actual.message == expected.message
+            && (expected.location.is_none()
+              || actual.location == expected.location)
+        })
+  }
+}
+
+fn strip_ansi(text: &str) -> String {
+  let mut stripped = String::new();
+  let mut escape = false;
+
+  for c in text.chars() {
+    match (escape, c) {
+      (false, '\x1b') => escape = true,
+      (false, c) => stripped.push(c),
+      (true, 'm') => escape = false,
+      (true, _) => {}
+    }
+  }
+
+  stripped
+}
+
+fn scripts(dir: &Path) -> Vec<PathBuf> {
+  let mut scripts = Vec::new();
+
+  for entry in fs::read_dir(dir).unwrap() {
+    let path = entry.unwrap().path();
+
+    if path.is_dir() {
+      scripts.extend(self::scripts(&path));
+    } else if path.extension().is_some_and(|extension| extension == "lox") {
+      scripts.push(path);
+    }
+  }
+
+  scripts.sort();
+
+  scripts
+}
+
+/// Whether the reference script `name` is listed in `SKIP`, returning the
+/// reason if so.
+fn skipped(name: &Path) -> Option<&'static str> {
+  SKIP
+    .iter()
+    .find(|(skipped, _)| name.starts_with(skipped))
+    .map(|(_, reason)| *reason)
+}
+
+#[test]
+fn conformance() {
+  let tests = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests");
+
+  let mut failures = Vec::new();
+
+  let mut scripts = Vec::new();
+
+  for (root, skip) in [("lox", true), ("local", false)] {
+    let root = tests.join(root);
+
+    for path in self::scripts(&root) {
+      let name = path.strip_prefix(&root).unwrap();
+
+      match skipped(name).filter(|_| skip) {
+        Some(reason) => eprintln!("SKIP {} ({})", name.display(), reason),
+        None => scripts.push(path),
+      }
+    }
+  }
+
+  for path in &scripts {
+    let name = path.strip_prefix(&tests).unwrap().display();
+
+    let expected = Outcome::expected(&fs::read_to_string(path).unwrap());
+
+    let actual = Outcome::actual(path);
+
+    if actual.matches(&expected) {
+      eprintln!("PASS {}", name);
+    } else {
+      eprintln!("FAIL {}", name);
+      failures.push(format!(
+        "{}\n  expected: {:?}\n  actual:   {:?}",
+        name, expected, actual
+      ));
+    }
+  }
+
+  assert!(
+    failures.is_empty(),
+    "{} of {} scripts failed:\n{}",
+    failures.len(),
+    scripts.len(),
+    failures.join("\n")
+  );
+}